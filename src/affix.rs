@@ -0,0 +1,288 @@
+use crate::constants::*;
+use crate::item::create_item;
+use crate::map::{check_depth_table, from_dungeon_level};
+use crate::object::{Equipment, Grip, Item, Object, Slot, Stack};
+use rand::Rng;
+use rand::distr::Distribution;
+use rand::distr::weighted::WeightedIndex;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+use std::sync::OnceLock;
+use tcod::colors::*;
+
+static AFFIXES: OnceLock<Vec<AffixTemplate>> = OnceLock::new();
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AffixTemplate {
+    pub name: String,
+    pub kind: AffixKind,
+    pub target: AffixTarget,
+    #[serde(default)]
+    pub spawn_weight: Vec<(u32, u32)>,
+    #[serde(default)]
+    pub max_hp_bonus: i32,
+    #[serde(default)]
+    pub defense_bonus: i32,
+    #[serde(default)]
+    pub power_bonus: i32,
+    #[serde(default)]
+    pub fire_damage: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum AffixTarget {
+    Weapon,
+    Armour,
+    Jewelry,
+    Any,
+}
+
+impl AffixTarget {
+    // ammunition is left alone so that it keeps stacking
+    fn of(equipment: &Equipment) -> Option<AffixTarget> {
+        match equipment.slot {
+            Slot::Quiver => None,
+            Slot::Ranged => Some(AffixTarget::Weapon),
+            Slot::LeftRing | Slot::RightRing | Slot::Amulet => Some(AffixTarget::Jewelry),
+            _ if equipment.grip != Grip::None => Some(AffixTarget::Weapon),
+            _ => Some(AffixTarget::Armour),
+        }
+    }
+
+    fn accepts(self, target: AffixTarget) -> bool {
+        self == AffixTarget::Any || self == target
+    }
+}
+
+impl AffixTemplate {
+    pub fn spawn_weight_at(&self, level: u32) -> u32 {
+        from_dungeon_level(&self.spawn_weight, level)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rarity {
+    Common,
+    Magic,
+    Rare,
+    Artifact,
+}
+
+impl Rarity {
+    pub fn roll(level: u32) -> Rarity {
+        let roll = rand::rng().random_range(0..100);
+        let artifact = from_dungeon_level(ARTIFACT_ITEM_CHANCE, level);
+        let rare = artifact + from_dungeon_level(RARE_ITEM_CHANCE, level);
+        let magic = rare + from_dungeon_level(MAGIC_ITEM_CHANCE, level);
+        if roll < artifact {
+            Rarity::Artifact
+        } else if roll < rare {
+            Rarity::Rare
+        } else if roll < magic {
+            Rarity::Magic
+        } else {
+            Rarity::Common
+        }
+    }
+
+    pub fn color(self) -> Option<Color> {
+        match self {
+            Rarity::Common => None,
+            Rarity::Magic => Some(LIGHT_BLUE),
+            Rarity::Rare => Some(LIGHT_YELLOW),
+            Rarity::Artifact => Some(ORANGE),
+        }
+    }
+
+    fn affixes(self) -> Vec<AffixKind> {
+        match self {
+            Rarity::Common => vec![],
+            Rarity::Magic if rand::rng().random_bool(0.5) => vec![AffixKind::Prefix],
+            Rarity::Magic => vec![AffixKind::Suffix],
+            Rarity::Rare | Rarity::Artifact => vec![AffixKind::Prefix, AffixKind::Suffix],
+        }
+    }
+}
+
+pub fn load_affixes(path: &str) -> Result<(), Box<dyn Error>> {
+    let mut json = String::new();
+    let mut file = File::open(path)?;
+    file.read_to_string(&mut json)?;
+    let templates = parse_affixes(&json)?;
+    let _ = AFFIXES.set(templates);
+    Ok(())
+}
+
+pub fn parse_affixes(json: &str) -> Result<Vec<AffixTemplate>, Box<dyn Error>> {
+    let templates = serde_json::from_str::<Vec<AffixTemplate>>(json)?;
+    validate_affixes(&templates)?;
+    Ok(templates)
+}
+
+fn validate_affixes(templates: &[AffixTemplate]) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for template in templates {
+        if template.name.is_empty() {
+            return Err("affix with an empty name".into());
+        }
+        if !names.insert(template.name.as_str()) {
+            return Err(format!("affix '{}' is defined twice", template.name));
+        }
+        check_depth_table(&template.spawn_weight).map_err(|e| {
+            format!(
+                "affix '{}' has a bad spawn_weight table: {}",
+                template.name, e
+            )
+        })?;
+        let bonuses = [
+            template.max_hp_bonus,
+            template.defense_bonus,
+            template.power_bonus,
+            template.fire_damage,
+        ];
+        if bonuses.iter().any(|&bonus| bonus < 0) || bonuses.iter().all(|&bonus| bonus == 0) {
+            return Err(format!("affix '{}' has bad bonuses", template.name));
+        }
+    }
+    Ok(())
+}
+
+pub fn affix_templates() -> &'static [AffixTemplate] {
+    AFFIXES.get().expect("affix templates were not loaded")
+}
+
+pub fn generate_item(item: Item, x: i32, y: i32, level: u32) -> Object {
+    let mut object = create_item(item, x, y);
+    if rand::rng().random_range(0..100) < CURSED_ITEM_CHANCE {
+        curse(&mut object);
+    } else {
+        enchant(&mut object, Rarity::roll(level), level);
+    }
+    object
+}
+
+// turns whatever the item was good for against its wearer
+pub fn curse(item: &mut Object) {
+    if item.has::<Stack>() {
+        return;
+    }
+    let Some(equipment) = item.get_mut::<Equipment>() else {
+        return;
+    };
+    equipment.cursed = true;
+    equipment.max_hp_bonus = -equipment.max_hp_bonus.abs();
+    equipment.defense_bonus = -equipment.defense_bonus.abs();
+    equipment.power_bonus = -equipment.power_bonus.abs();
+    equipment.fire_damage = 0;
+    if equipment.max_hp_bonus + equipment.defense_bonus + equipment.power_bonus == 0 {
+        equipment.defense_bonus = -CURSE_PENALTY;
+    }
+}
+
+pub fn enchant(item: &mut Object, rarity: Rarity, level: u32) {
+    if item.has::<Stack>() {
+        return;
+    }
+    let Some(mut equipment) = item.get::<Equipment>().copied() else {
+        return;
+    };
+    let Some(target) = AffixTarget::of(&equipment) else {
+        return;
+    };
+
+    let multiplier = match rarity {
+        Rarity::Artifact => ARTIFACT_AFFIX_MULTIPLIER,
+        _ => 1,
+    };
+    let mut rng = rand::rng();
+    let mut prefix = None;
+    let mut suffix = None;
+    for kind in rarity.affixes() {
+        let pool: Vec<&AffixTemplate> = affix_templates()
+            .iter()
+            .filter(|affix| affix.kind == kind && affix.target.accepts(target))
+            .collect();
+        let Ok(dist) = WeightedIndex::new(pool.iter().map(|affix| affix.spawn_weight_at(level)))
+        else {
+            continue;
+        };
+        let affix = pool[dist.sample(&mut rng)];
+        equipment.max_hp_bonus += affix.max_hp_bonus * multiplier;
+        equipment.defense_bonus += affix.defense_bonus * multiplier;
+        equipment.power_bonus += affix.power_bonus * multiplier;
+        equipment.fire_damage += affix.fire_damage * multiplier;
+        match kind {
+            AffixKind::Prefix => prefix = Some(affix.name.as_str()),
+            AffixKind::Suffix => suffix = Some(affix.name.as_str()),
+        }
+    }
+    if prefix.is_none() && suffix.is_none() {
+        return;
+    }
+
+    let mut name = item.name.clone();
+    if let Some(prefix) = prefix {
+        name = format!("{} {}", prefix, name);
+    }
+    if let Some(suffix) = suffix {
+        name = format!("{} {}", name, suffix);
+    }
+    if rarity == Rarity::Artifact {
+        name = format!("ancient {}", name);
+    }
+    item.name = name;
+    if let Some(color) = rarity.color() {
+        item.color = color;
+    }
+    item.insert(equipment);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::load_test_templates;
+
+    fn sword() -> Object {
+        load_test_templates();
+        create_item(Item::Sword, 0, 0)
+    }
+
+    #[test]
+    fn rare_items_get_a_prefix_and_a_suffix() {
+        AFFIXES.get_or_init(|| parse_affixes(include_str!("../affixes.json")).unwrap());
+
+        let mut common = sword();
+        enchant(&mut common, Rarity::Common, 5);
+        assert_eq!(common, sword());
+
+        let mut rare = sword();
+        enchant(&mut rare, Rarity::Rare, 5);
+        let words = rare.name.split(' ').count();
+        assert!(words >= 4, "{}", rare.name);
+        assert!(rare.name.contains(" sword of "), "{}", rare.name);
+        assert_eq!(rare.color, LIGHT_YELLOW);
+        let equipment = rare.get::<Equipment>().unwrap();
+        let bonus = equipment.max_hp_bonus
+            + equipment.defense_bonus
+            + equipment.power_bonus
+            + equipment.fire_damage;
+        assert!(bonus > sword().get::<Equipment>().unwrap().power_bonus);
+    }
+
+    #[test]
+    fn affixes_without_bonuses_are_rejected() {
+        let json = r#"[{"name": "dull", "kind": "Prefix", "target": "Any"}]"#;
+        let error = parse_affixes(json).unwrap_err().to_string();
+        assert!(error.contains("bad bonuses"), "{}", error);
+    }
+}
@@ -1,18 +1,39 @@
+use crate::boss::{Boss, BossPhase};
 use crate::constants::*;
+use crate::entity::{EntityId, Objects};
 use crate::game::{Game, Tcod};
-use crate::map::{Map, is_blocked};
-use crate::mut_two;
-use crate::object::{Ai, Object};
+use crate::map::{Map, has_line_of_sight, is_blocked, is_corridor};
+use crate::monster::{create_monster, wear};
+use crate::object::{Ai, Fighter, Inventory, Item, Order, Spawned, grant_xp};
+use crate::projectile::{Projectile, fire_projectile, has_line_of_fire};
+use crate::spell::{self, Spell};
+use crate::status::StatusKind;
 use rand::Rng;
+use std::cmp;
 
-pub fn move_by(id: usize, dx: i32, dy: i32, map: &Map, objects: &mut [Object]) {
+pub fn move_by(id: EntityId, dx: i32, dy: i32, map: &Map, objects: &mut Objects) {
     let (x, y) = objects[id].pos();
     if !is_blocked(x + dx, y + dy, map, objects) {
         objects[id].set_pos(x + dx, y + dy);
     }
 }
 
-fn move_towards(id: usize, target_x: i32, target_y: i32, map: &Map, objects: &mut [Object]) {
+fn move_away_from(id: EntityId, target_x: i32, target_y: i32, map: &Map, objects: &mut Objects) {
+    let dx = (objects[id].x - target_x).signum();
+    let dy = (objects[id].y - target_y).signum();
+    for (step_x, step_y) in [(dx, dy), (dx, 0), (0, dy)] {
+        if (step_x, step_y) == (0, 0) {
+            continue;
+        }
+        let (x, y) = objects[id].pos();
+        if !is_blocked(x + step_x, y + step_y, map, objects) {
+            objects[id].set_pos(x + step_x, y + step_y);
+            return;
+        }
+    }
+}
+
+fn move_towards(id: EntityId, target_x: i32, target_y: i32, map: &Map, objects: &mut Objects) {
     let dx = target_x - objects[id].x;
     let dy = target_y - objects[id].y;
     let distance = ((dx.pow(2) + dy.pow(2)) as f32).sqrt();
@@ -22,85 +43,731 @@ fn move_towards(id: usize, target_x: i32, target_y: i32, map: &Map, objects: &mu
     move_by(id, dx, dy, map, objects);
 }
 
-pub fn ai_take_turn(monster_id: usize, tcod: &Tcod, game: &mut Game, objects: &mut [Object]) {
+pub fn ai_take_turn(monster_id: EntityId, tcod: &mut Tcod, game: &mut Game, objects: &mut Objects) {
     use Ai::*;
-    if let Some(ai) = objects[monster_id].ai.take() {
+    if objects[monster_id].has_status(StatusKind::Frozen) {
+        return;
+    }
+    if objects[monster_id].has_status(StatusKind::Confused) {
+        move_or_attack(
+            monster_id,
+            rand::rng().random_range(-1..2),
+            rand::rng().random_range(-1..2),
+            game,
+            objects,
+        );
+        return;
+    }
+    if let Some(ai) = objects[monster_id].remove::<Ai>() {
         let new_ai = match ai {
             Basic => ai_basic(monster_id, tcod, game, objects),
-            Confused {
-                previous_ai,
-                num_turns,
-            } => ai_confused(monster_id, tcod, game, objects, previous_ai, num_turns),
-            Frozen {
-                previous_ai,
-                num_turns,
-            } => ai_frozen(monster_id, tcod, game, objects, previous_ai, num_turns),
+            Ranged { range, projectile } => {
+                ai_ranged(monster_id, tcod, game, objects, range, projectile)
+            }
+            Caster { spells, cooldown } => {
+                ai_caster(monster_id, tcod, game, objects, spells, cooldown)
+            }
+            Companion { order } => ai_companion(monster_id, tcod, game, objects, order),
+            Pack { leader } => ai_pack(monster_id, tcod, game, objects, leader),
+            Fleeing { turns } => ai_fleeing(monster_id, tcod, game, objects, turns),
+            Boss {
+                boss,
+                phase,
+                cooldown,
+            } => ai_boss(monster_id, tcod, game, objects, boss, phase, cooldown),
+            Summoner { cooldown } => ai_summoner(monster_id, tcod, game, objects, cooldown),
+            Spawner { monster, timer } => {
+                ai_spawner(monster_id, tcod, game, objects, monster, timer)
+            }
         };
-        objects[monster_id].ai = Some(new_ai);
+        objects[monster_id].insert(new_ai);
+    }
+    monster_pick_up(monster_id, tcod, game, objects);
+}
+
+fn monster_pick_up(monster_id: EntityId, tcod: &Tcod, game: &mut Game, objects: &mut Objects) {
+    let carried = match objects[monster_id].get::<Inventory>() {
+        Some(inventory) if inventory.picks_up_items && objects[monster_id].alive => {
+            inventory.items.len()
+        }
+        _ => return,
+    };
+    if carried >= MONSTER_CARRY_LIMIT {
+        return;
+    }
+    let pos = objects[monster_id].pos();
+    let item_id = objects.position(|object| object.pos() == pos && object.has::<Item>());
+    if let Some(item) = item_id.and_then(|item_id| objects.remove(item_id)) {
+        let name = game.identification.name(&item);
+        let worn = wear(&mut objects[monster_id], item);
+        if tcod.fov.is_in_fov(pos.0, pos.1) {
+            let verb = if worn { "puts on" } else { "picks up" };
+            game.messages.add(
+                format!("The {} {} a {}.", objects[monster_id].name, verb, name),
+                tcod::colors::LIGHT_GREY,
+            );
+        }
+    }
+}
+
+fn move_or_attack(id: EntityId, dx: i32, dy: i32, game: &mut Game, objects: &mut Objects) {
+    let (x, y) = (objects[id].x + dx, objects[id].y + dy);
+    let target_id = objects.position(|object| object.has::<Fighter>() && object.pos() == (x, y));
+    match target_id {
+        Some(target_id) if target_id != id => melee(id, target_id, game, objects),
+        Some(_) => {}
+        None => move_by(id, dx, dy, &game.map, objects),
     }
 }
 
-fn ai_basic(monster_id: usize, tcod: &Tcod, game: &mut Game, objects: &mut [Object]) -> Ai {
+fn melee(attacker_id: EntityId, target_id: EntityId, game: &mut Game, objects: &mut Objects) {
+    let (attacker, target) = objects.get_two_mut(attacker_id, target_id);
+    if let Some(xp) = attacker.attack(target, game) {
+        grant_xp(attacker_id, xp, game, objects);
+    }
+}
+
+fn find_target(
+    monster_id: EntityId,
+    tcod: &Tcod,
+    game: &Game,
+    objects: &Objects,
+) -> Option<EntityId> {
+    let monster = &objects[monster_id];
+    objects
+        .query::<Fighter>()
+        .map(|(id, object, _)| (id, object))
+        .filter(|&(id, object)| {
+            id != monster_id
+                && object.alive
+                && monster.faction.is_hostile_to(object.faction)
+                && tcod.fov.is_in_fov(object.x, object.y)
+                && has_line_of_sight(monster.pos(), object.pos(), &game.map)
+        })
+        .min_by(|(_, a), (_, b)| {
+            monster
+                .distance_to(a)
+                .partial_cmp(&monster.distance_to(b))
+                .unwrap()
+        })
+        .map(|(id, _)| id)
+}
+
+fn ai_basic(monster_id: EntityId, tcod: &Tcod, game: &mut Game, objects: &mut Objects) -> Ai {
     let (monster_x, monster_y) = objects[monster_id].pos();
-    if tcod.fov.is_in_fov(monster_x, monster_y) {
-        if objects[monster_id].distance_to(&objects[PLAYER]) >= 2.0 {
-            let (player_x, player_y) = objects[PLAYER].pos();
-            move_towards(monster_id, player_x, player_y, &game.map, objects);
-        } else if objects[PLAYER].fighter.map_or(false, |f| f.hp > 0) {
-            let (monster, player) = mut_two(monster_id, PLAYER, objects);
-            monster.attack(player, game);
+    if !tcod.fov.is_in_fov(monster_x, monster_y) {
+        return Ai::Basic;
+    }
+    if let Some(target_id) = find_target(monster_id, tcod, game, objects) {
+        if objects[monster_id].distance_to(&objects[target_id]) >= 2.0 {
+            let (target_x, target_y) = objects[target_id].pos();
+            move_towards(monster_id, target_x, target_y, &game.map, objects);
+        } else {
+            melee(monster_id, target_id, game, objects);
         }
     }
     Ai::Basic
 }
 
-fn ai_confused(
-    monster_id: usize,
-    _tcod: &Tcod,
+fn ai_ranged(
+    monster_id: EntityId,
+    tcod: &mut Tcod,
     game: &mut Game,
-    objects: &mut [Object],
-    previous_ai: Box<Ai>,
-    num_turns: i32,
+    objects: &mut Objects,
+    range: i32,
+    projectile: Projectile,
 ) -> Ai {
-    if num_turns >= 0 {
-        move_by(
-            monster_id,
-            rand::rng().random_range(-1..2),
-            rand::rng().random_range(-1..2),
-            &game.map,
-            objects,
-        );
-        Ai::Confused {
-            previous_ai: previous_ai,
-            num_turns: num_turns - 1,
+    let (monster_x, monster_y) = objects[monster_id].pos();
+    if !tcod.fov.is_in_fov(monster_x, monster_y) {
+        return Ai::Ranged { range, projectile };
+    }
+    if let Some(target_id) = find_target(monster_id, tcod, game, objects) {
+        let (target_x, target_y) = objects[target_id].pos();
+        let distance = objects[monster_id].distance_to(&objects[target_id]);
+
+        if distance < RANGED_KEEP_DISTANCE {
+            move_away_from(monster_id, target_x, target_y, &game.map, objects);
+            if objects[monster_id].pos() != (monster_x, monster_y) {
+                return Ai::Ranged { range, projectile };
+            }
+        }
+
+        if distance < 2.0 {
+            melee(monster_id, target_id, game, objects);
+        } else if distance <= range as f32
+            && has_line_of_fire(monster_id, target_id, &game.map, objects)
+        {
+            fire_projectile(
+                monster_id,
+                (target_x, target_y),
+                projectile,
+                tcod,
+                game,
+                objects,
+            );
+        } else {
+            move_towards(monster_id, target_x, target_y, &game.map, objects);
+        }
+    }
+    Ai::Ranged { range, projectile }
+}
+
+fn ai_caster(
+    monster_id: EntityId,
+    tcod: &Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+    spells: Vec<Spell>,
+    cooldown: i32,
+) -> Ai {
+    let (monster_x, monster_y) = objects[monster_id].pos();
+    let target_id = if tcod.fov.is_in_fov(monster_x, monster_y) {
+        find_target(monster_id, tcod, game, objects)
+    } else {
+        None
+    };
+    let target_id = match target_id {
+        Some(target_id) => target_id,
+        None => {
+            return Ai::Caster {
+                spells,
+                cooldown: cmp::max(cooldown - 1, 0),
+            };
+        }
+    };
+
+    if cooldown <= 0 {
+        for &spell in &spells {
+            if try_cast(monster_id, target_id, spell, tcod, game, objects) {
+                return Ai::Caster {
+                    spells,
+                    cooldown: CASTER_COOLDOWN,
+                };
+            }
         }
+    }
+
+    if objects[monster_id].distance_to(&objects[target_id]) >= 2.0 {
+        let (target_x, target_y) = objects[target_id].pos();
+        move_towards(monster_id, target_x, target_y, &game.map, objects);
     } else {
-        game.messages.add(
-            format!("The {} is no longer confused!", objects[monster_id].name),
-            tcod::colors::RED,
-        );
-        *previous_ai
+        melee(monster_id, target_id, game, objects);
+    }
+    Ai::Caster {
+        spells,
+        cooldown: cmp::max(cooldown - 1, 0),
     }
 }
 
-fn ai_frozen(
-    monster_id: usize,
-    _tcod: &Tcod,
+fn ai_companion(
+    companion_id: EntityId,
+    tcod: &Tcod,
     game: &mut Game,
-    objects: &mut [Object],
-    previous_ai: Box<Ai>,
-    num_turns: i32,
+    objects: &mut Objects,
+    order: Order,
 ) -> Ai {
-    if num_turns >= 0 {
-        Ai::Frozen {
-            previous_ai: previous_ai,
-            num_turns: num_turns - 1,
+    let order = match order {
+        Order::Attack(target_id)
+            if !objects.contains(target_id)
+                || !objects[target_id].alive
+                || !objects[companion_id]
+                    .faction
+                    .is_hostile_to(objects[target_id].faction) =>
+        {
+            Order::Follow
         }
+        order => order,
+    };
+
+    let target_id = match order {
+        Order::Attack(target_id) => Some(target_id),
+        Order::Stay => find_target(companion_id, tcod, game, objects)
+            .filter(|&id| objects[companion_id].distance_to(&objects[id]) < 2.0),
+        Order::Follow => find_target(companion_id, tcod, game, objects)
+            .filter(|&id| objects.player().distance_to(&objects[id]) <= COMPANION_LEASH),
+    };
+
+    match target_id {
+        Some(target_id) if objects[companion_id].distance_to(&objects[target_id]) < 2.0 => {
+            melee(companion_id, target_id, game, objects);
+        }
+        Some(target_id) => {
+            let (target_x, target_y) = objects[target_id].pos();
+            move_towards(companion_id, target_x, target_y, &game.map, objects);
+        }
+        None if order == Order::Follow
+            && objects[companion_id].distance_to(objects.player()) > COMPANION_FOLLOW_DISTANCE =>
+        {
+            let (player_x, player_y) = objects.player().pos();
+            move_towards(companion_id, player_x, player_y, &game.map, objects);
+        }
+        None => {}
+    }
+    Ai::Companion { order }
+}
+
+fn pack_leader_alive(leader: EntityId, objects: &Objects) -> bool {
+    objects
+        .get(leader)
+        .is_some_and(|object| object.alive && matches!(object.get::<Ai>(), Some(Ai::Pack { .. })))
+}
+
+fn pack_members_near(leader: EntityId, x: i32, y: i32, radius: f32, objects: &Objects) -> usize {
+    objects
+        .iter()
+        .filter(|object| {
+            object.alive
+                && object.get::<Ai>() == Some(&Ai::Pack { leader })
+                && object.distance(x, y) <= radius
+        })
+        .count()
+}
+
+// the free tile next to the target that the rest of the pack covers least,
+// so packs spread around their prey instead of queueing on one side
+fn flanking_tile(
+    monster_id: EntityId,
+    leader: EntityId,
+    target_id: EntityId,
+    map: &Map,
+    objects: &Objects,
+) -> Option<(i32, i32)> {
+    let monster = &objects[monster_id];
+    let pack: Vec<(i32, i32)> = objects
+        .iter()
+        .filter(|object| {
+            object.alive
+                && object.pos() != monster.pos()
+                && object.get::<Ai>() == Some(&Ai::Pack { leader })
+        })
+        .map(|object| object.pos())
+        .collect();
+    let crowding = |(x, y): (i32, i32)| {
+        pack.iter()
+            .filter(|&&(pack_x, pack_y)| (pack_x - x).abs() <= 1 && (pack_y - y).abs() <= 1)
+            .count()
+    };
+
+    let (target_x, target_y) = objects[target_id].pos();
+    let mut tiles = vec![];
+    for dx in -1..=1 {
+        for dy in -1..=1 {
+            let (x, y) = (target_x + dx, target_y + dy);
+            if (dx, dy) != (0, 0) && !is_blocked(x, y, map, objects) {
+                tiles.push((x, y));
+            }
+        }
+    }
+    tiles.into_iter().min_by(|&a, &b| {
+        crowding(a).cmp(&crowding(b)).then_with(|| {
+            monster
+                .distance(a.0, a.1)
+                .partial_cmp(&monster.distance(b.0, b.1))
+                .unwrap()
+        })
+    })
+}
+
+fn step_towards(
+    id: EntityId,
+    leader: EntityId,
+    target_x: i32,
+    target_y: i32,
+    map: &Map,
+    objects: &mut Objects,
+) {
+    let (x, y) = objects[id].pos();
+    let mut best = None;
+    let mut best_distance = objects[id].distance(target_x, target_y);
+    for dx in -1..=1 {
+        for dy in -1..=1 {
+            let (step_x, step_y) = (x + dx, y + dy);
+            if (dx, dy) == (0, 0) || is_blocked(step_x, step_y, map, objects) {
+                continue;
+            }
+            // don't queue up in a corridor behind another pack member
+            if is_corridor(step_x, step_y, map)
+                && pack_members_near(leader, step_x, step_y, 1.5, objects) > 0
+            {
+                continue;
+            }
+            let distance = ((target_x - step_x).pow(2) + (target_y - step_y).pow(2)) as f32;
+            let distance = distance.sqrt();
+            if distance < best_distance {
+                best = Some((step_x, step_y));
+                best_distance = distance;
+            }
+        }
+    }
+    if let Some((step_x, step_y)) = best {
+        objects[id].set_pos(step_x, step_y);
+    }
+}
+
+fn ai_pack(
+    monster_id: EntityId,
+    tcod: &Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+    leader: EntityId,
+) -> Ai {
+    let is_leader = leader == monster_id;
+    let (monster_x, monster_y) = objects[monster_id].pos();
+    if !is_leader && !pack_leader_alive(leader, objects) {
+        if tcod.fov.is_in_fov(monster_x, monster_y) {
+            game.messages.add(
+                format!(
+                    "With its leader gone, the {} scatters in panic!",
+                    objects[monster_id].name
+                ),
+                tcod::colors::LIGHT_GREY,
+            );
+        }
+        return Ai::Fleeing {
+            turns: PACK_FLEE_TURNS,
+        };
+    }
+
+    let (leader_x, leader_y) = objects[leader].pos();
+    if !tcod.fov.is_in_fov(monster_x, monster_y) && !tcod.fov.is_in_fov(leader_x, leader_y) {
+        return Ai::Pack { leader };
+    }
+
+    let target_id = match find_target(monster_id, tcod, game, objects) {
+        Some(target_id) => target_id,
+        None => {
+            if objects[monster_id].distance(leader_x, leader_y) > PACK_GATHER_RADIUS {
+                step_towards(monster_id, leader, leader_x, leader_y, &game.map, objects);
+            }
+            return Ai::Pack { leader };
+        }
+    };
+
+    if objects[monster_id].distance_to(&objects[target_id]) < 2.0 {
+        melee(monster_id, target_id, game, objects);
+        return Ai::Pack { leader };
+    }
+
+    // wait for the rest of the pack before closing in
+    let pack_size = pack_members_near(leader, monster_x, monster_y, f32::MAX, objects) + 1;
+    let gathered = pack_members_near(leader, monster_x, monster_y, PACK_GATHER_RADIUS, objects) + 1;
+    if gathered < cmp::min(PACK_ENGAGE_COUNT, pack_size) {
+        if !is_leader && objects[monster_id].distance(leader_x, leader_y) >= 2.0 {
+            step_towards(monster_id, leader, leader_x, leader_y, &game.map, objects);
+        }
+        return Ai::Pack { leader };
+    }
+
+    if let Some((flank_x, flank_y)) =
+        flanking_tile(monster_id, leader, target_id, &game.map, objects)
+    {
+        step_towards(monster_id, leader, flank_x, flank_y, &game.map, objects);
+    }
+    Ai::Pack { leader }
+}
+
+fn ai_fleeing(
+    monster_id: EntityId,
+    tcod: &Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+    turns: i32,
+) -> Ai {
+    if let Some(threat_id) = find_target(monster_id, tcod, game, objects) {
+        let (threat_x, threat_y) = objects[threat_id].pos();
+        move_away_from(monster_id, threat_x, threat_y, &game.map, objects);
+    }
+    if turns <= 1 {
+        Ai::Basic
     } else {
-        game.messages.add(
-            format!("The {} is no longer frozen!", objects[monster_id].name),
-            tcod::colors::RED,
-        );
-        *previous_ai
+        Ai::Fleeing { turns: turns - 1 }
+    }
+}
+
+fn ai_boss(
+    monster_id: EntityId,
+    tcod: &Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+    boss: Boss,
+    phase: BossPhase,
+    cooldown: i32,
+) -> Ai {
+    let (monster_x, monster_y) = objects[monster_id].pos();
+    if !tcod.fov.is_in_fov(monster_x, monster_y) {
+        return Ai::Boss {
+            boss,
+            phase,
+            cooldown,
+        };
+    }
+
+    let hp = objects[monster_id].get::<Fighter>().map_or(0, |f| f.hp);
+    let max_hp = objects[monster_id].max_hp();
+    let name = objects[monster_id].name.clone();
+    let phase = match phase {
+        BossPhase::Normal if hp * 2 <= max_hp => {
+            game.messages.add(
+                format!("{} roars, calling its minions to its side!", name),
+                tcod::colors::ORANGE,
+            );
+            for _ in 0..BOSS_MINION_COUNT {
+                game.spawn_queue.push(create_monster(
+                    boss.minion(),
+                    monster_x,
+                    monster_y,
+                    game.dungeon_level,
+                ));
+            }
+            BossPhase::Summoned
+        }
+        BossPhase::Summoned if hp * 4 <= max_hp => {
+            game.messages
+                .add(format!("{} flies into a rage!", name), tcod::colors::RED);
+            let monster = &mut objects[monster_id];
+            monster.color = tcod::colors::RED;
+            if let Some(fighter) = monster.get_mut::<Fighter>() {
+                fighter.base_power += BOSS_ENRAGE_POWER;
+            }
+            BossPhase::Enraged
+        }
+        phase => phase,
+    };
+
+    let target_id = match find_target(monster_id, tcod, game, objects) {
+        Some(target_id) => target_id,
+        None => {
+            return Ai::Boss {
+                boss,
+                phase,
+                cooldown: cmp::max(cooldown - 1, 0),
+            };
+        }
+    };
+
+    let distance = objects[monster_id].distance_to(&objects[target_id]);
+    let destination =
+        if boss.teleports() && phase != BossPhase::Normal && cooldown <= 0 && distance < 2.0 {
+            spell::blink_destination(monster_id, target_id, game, objects)
+        } else {
+            None
+        };
+    if let Some((x, y)) = destination {
+        spell::blink(monster_id, x, y, game, objects);
+        return Ai::Boss {
+            boss,
+            phase,
+            cooldown: BOSS_TELEPORT_COOLDOWN,
+        };
+    }
+
+    if distance >= 2.0 {
+        let (target_x, target_y) = objects[target_id].pos();
+        move_towards(monster_id, target_x, target_y, &game.map, objects);
+    } else {
+        melee(monster_id, target_id, game, objects);
+    }
+    Ai::Boss {
+        boss,
+        phase,
+        cooldown: cmp::max(cooldown - 1, 0),
+    }
+}
+
+fn ai_summoner(
+    monster_id: EntityId,
+    tcod: &Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+    cooldown: i32,
+) -> Ai {
+    let (monster_x, monster_y) = objects[monster_id].pos();
+    if !tcod.fov.is_in_fov(monster_x, monster_y) {
+        return Ai::Summoner { cooldown };
+    }
+
+    if cooldown <= 0 {
+        let corpse_id = objects.position(|object| {
+            object.is_corpse()
+                && object.distance(monster_x, monster_y) <= RAISE_DEAD_RANGE as f32
+                && !is_blocked(object.x, object.y, &game.map, objects)
+                && has_line_of_sight((monster_x, monster_y), object.pos(), &game.map)
+        });
+        if let Some(corpse_id) = corpse_id {
+            let (corpse_x, corpse_y) = objects[corpse_id].pos();
+            let mut zombie = create_monster("zombie", corpse_x, corpse_y, game.dungeon_level);
+            zombie.name = format!(
+                "{} zombie",
+                objects[corpse_id].name.trim_start_matches("remains of ")
+            );
+            zombie.faction = objects[monster_id].faction;
+            game.messages.add(
+                format!(
+                    "The {} chants, and the {} rises as a {}!",
+                    objects[monster_id].name, objects[corpse_id].name, zombie.name
+                ),
+                tcod::colors::DARK_VIOLET,
+            );
+            // the corpse is raised in place, so anything holding its id now points at the zombie
+            objects[corpse_id] = zombie;
+            return Ai::Summoner {
+                cooldown: RAISE_DEAD_COOLDOWN,
+            };
+        }
+    }
+
+    if let Some(target_id) = find_target(monster_id, tcod, game, objects) {
+        let (target_x, target_y) = objects[target_id].pos();
+        let distance = objects[monster_id].distance_to(&objects[target_id]);
+        if distance < RANGED_KEEP_DISTANCE {
+            move_away_from(monster_id, target_x, target_y, &game.map, objects);
+        }
+        if objects[monster_id].pos() == (monster_x, monster_y) && distance < 2.0 {
+            melee(monster_id, target_id, game, objects);
+        }
+    }
+    Ai::Summoner {
+        cooldown: cmp::max(cooldown - 1, 0),
+    }
+}
+
+fn ai_spawner(
+    monster_id: EntityId,
+    tcod: &Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+    monster: String,
+    timer: i32,
+) -> Ai {
+    // a nest nobody can see stays dormant
+    let (x, y) = objects[monster_id].pos();
+    if !tcod.fov.is_in_fov(x, y) {
+        return Ai::Spawner { monster, timer };
+    }
+    if timer > 1 {
+        return Ai::Spawner {
+            monster,
+            timer: timer - 1,
+        };
+    }
+
+    let brood = objects
+        .iter()
+        .filter(|object| {
+            object.alive && object.get::<Spawned>() == Some(&Spawned { nest: monster_id })
+        })
+        .count();
+    if brood < NEST_MAX_SPAWNS {
+        let mut spawn = create_monster(&monster, x, y, game.dungeon_level);
+        spawn.insert(Spawned { nest: monster_id });
+        // queued objects are inserted once every monster has had its turn
+        game.spawn_queue.push(spawn);
+    }
+    Ai::Spawner {
+        monster,
+        timer: NEST_SPAWN_INTERVAL,
+    }
+}
+
+fn try_cast(
+    monster_id: EntityId,
+    target_id: EntityId,
+    spell: Spell,
+    tcod: &Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+) -> bool {
+    let distance = objects[monster_id].distance_to(&objects[target_id]);
+    let target = &objects[target_id];
+    let target = match spell {
+        Spell::Blink if distance < 2.0 => {
+            spell::blink_destination(monster_id, target_id, game, objects)
+                .map(|(x, y)| (monster_id, x, y))
+        }
+        Spell::Heal => {
+            wounded_ally(monster_id, tcod, objects).map(|id| (id, objects[id].x, objects[id].y))
+        }
+        Spell::Lightning if distance <= LIGHTNING_RANGE as f32 => {
+            Some((target_id, target.x, target.y))
+        }
+        Spell::Confuse
+            if distance <= CONFUSE_RANGE as f32 && !target.has_status(StatusKind::Confused) =>
+        {
+            Some((target_id, target.x, target.y))
+        }
+        Spell::Freeze
+            if distance <= FREEZE_RANGE as f32 && !target.has_status(StatusKind::Frozen) =>
+        {
+            Some((target_id, target.x, target.y))
+        }
+        Spell::Fireball
+            if distance > (FIREBALL_RADIUS + 1) as f32 && distance <= FIREBALL_RANGE as f32 =>
+        {
+            Some((target_id, target.x, target.y))
+        }
+        Spell::Charm if distance <= CHARM_RANGE as f32 && target_id != objects.player_id() => {
+            Some((target_id, target.x, target.y))
+        }
+        _ => None,
+    };
+    let (target_id, x, y) = match target {
+        Some(target) => target,
+        None => return false,
+    };
+
+    game.messages.add(
+        format!("The {} casts {}!", objects[monster_id].name, spell.name()),
+        tcod::colors::LIGHT_CYAN,
+    );
+    match spell {
+        Spell::Heal => spell::heal(target_id, MONSTER_HEAL_AMOUNT, game, objects),
+        Spell::Lightning => spell::lightning(monster_id, target_id, game, objects),
+        Spell::Confuse => spell::confuse(target_id, game, objects),
+        Spell::Freeze => spell::freeze(target_id, game, objects),
+        Spell::Fireball => spell::fireball(monster_id, x, y, game, objects),
+        Spell::Blink => spell::blink(monster_id, x, y, game, objects),
+        Spell::Charm => spell::charm(monster_id, target_id, game, objects),
+    }
+    true
+}
+
+fn wounded_ally(monster_id: EntityId, tcod: &Tcod, objects: &Objects) -> Option<EntityId> {
+    objects.position(|object| {
+        object.has::<Ai>()
+            && object.faction == objects[monster_id].faction
+            && object
+                .get::<Fighter>()
+                .is_some_and(|f| f.hp * 2 <= object.max_hp())
+            && object.distance_to(&objects[monster_id]) <= HEAL_ALLY_RANGE as f32
+            && tcod.fov.is_in_fov(object.x, object.y)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Tile;
+    use crate::object::Object;
+    use tcod::colors::WHITE;
+
+    fn wolf(x: i32, y: i32) -> Object {
+        let mut wolf = Object::new(x, y, 'w', WHITE, "wolf", true);
+        wolf.alive = true;
+        wolf
+    }
+
+    #[test]
+    fn pack_members_flank_from_opposite_sides() {
+        let map = vec![vec![Tile::empty(); 10]; 10];
+        let mut objects = Objects::new(Object::new(5, 5, '@', WHITE, "player", true));
+        let leader = objects.insert(wolf(4, 5));
+        let follower = objects.insert(wolf(1, 5));
+        for id in [leader, follower] {
+            objects[id].insert(Ai::Pack { leader });
+        }
+
+        let player_id = objects.player_id();
+        let (x, _) = flanking_tile(follower, leader, player_id, &map, &objects).unwrap();
+        assert_eq!(x, 6);
     }
 }
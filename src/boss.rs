@@ -0,0 +1,119 @@
+use crate::affix::{Rarity, enchant};
+use crate::constants::*;
+use crate::game::Game;
+use crate::item::create_item;
+use crate::monster::boss_template;
+use crate::object::{DeathCallback, Fighter, Item, Object, monster_death};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Boss {
+    GoblinKing,
+    Necromancer,
+    SpiderQueen,
+    DemonLord,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BossPhase {
+    Normal,
+    Summoned,
+    Enraged,
+}
+
+impl Boss {
+    pub const ALL: [Boss; 4] = [
+        Boss::GoblinKing,
+        Boss::Necromancer,
+        Boss::SpiderQueen,
+        Boss::DemonLord,
+    ];
+
+    pub fn for_level(level: u32) -> Option<Boss> {
+        match level {
+            3 => Some(Boss::GoblinKing),
+            5 => Some(Boss::Necromancer),
+            7 => Some(Boss::SpiderQueen),
+            9 => Some(Boss::DemonLord),
+            _ => None,
+        }
+    }
+
+    pub fn minion(self) -> &'static str {
+        match self {
+            Boss::GoblinKing => "goblin",
+            Boss::Necromancer => "skeleton",
+            Boss::SpiderQueen => "giant spider",
+            Boss::DemonLord => "dark mage",
+        }
+    }
+
+    pub fn teleports(self) -> bool {
+        match self {
+            Boss::GoblinKing | Boss::SpiderQueen => false,
+            Boss::Necromancer | Boss::DemonLord => true,
+        }
+    }
+}
+
+pub fn create_boss(boss: Boss, x: i32, y: i32) -> Object {
+    let template = boss_template(boss);
+    let [r, g, b] = template.color;
+    let mut monster = Object::new(
+        x,
+        y,
+        template.glyph,
+        Color::new(r, g, b),
+        &template.name,
+        true,
+    );
+    monster.insert(Fighter {
+        base_max_hp: template.hp,
+        hp: template.hp,
+        base_defense: template.defense,
+        base_power: template.power,
+        xp: template.xp,
+        on_death: DeathCallback::Boss,
+        on_hit: template.on_hit,
+    });
+    monster.faction = template.faction;
+    monster.insert(template.ai.clone());
+    monster.always_visible = true;
+    monster.alive = true;
+    monster
+}
+
+pub fn boss_death(monster: &mut Object, game: &mut Game) {
+    game.messages.add(
+        format!(
+            "{} has been slain! Its treasure spills onto the floor.",
+            monster.name
+        ),
+        LIGHT_GREEN,
+    );
+    let (x, y) = monster.pos();
+    let mut rng = rand::rng();
+    let equipment = [Item::Sword, Item::Shield];
+    let mut prize = create_item(equipment[rng.random_range(0..equipment.len())], x, y);
+    enchant(&mut prize, Rarity::Rare, game.dungeon_level);
+    game.spawn_queue.push(prize);
+    let consumables = [
+        Item::Heal,
+        Item::Lightning,
+        Item::Fireball,
+        Item::Confuse,
+        Item::Blink,
+        Item::Freeze,
+        Item::Charm,
+    ];
+    for _ in 0..rng.random_range(BOSS_MIN_LOOT..=BOSS_MAX_LOOT) {
+        game.spawn_queue.push(create_item(
+            consumables[rng.random_range(0..consumables.len())],
+            x,
+            y,
+        ));
+    }
+    monster_death(monster, game);
+}
@@ -0,0 +1,95 @@
+use crate::monster::Loot;
+use crate::object::{
+    Ai, Charges, Equipment, Fighter, Gold, Inventory, Item, Launcher, Spawned, Stack,
+};
+use serde::{Deserialize, Serialize};
+
+pub trait Component: Sized + 'static {
+    fn wrap(self) -> AnyComponent;
+    fn unwrap(component: AnyComponent) -> Option<Self>;
+    fn unwrap_ref(component: &AnyComponent) -> Option<&Self>;
+    fn unwrap_mut(component: &mut AnyComponent) -> Option<&mut Self>;
+}
+
+// adding a capability is one more name here, not one more field on every object
+macro_rules! components {
+    ($($name:ident),* $(,)?) => {
+        #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+        pub enum AnyComponent {
+            $($name($name),)*
+        }
+
+        $(
+            impl Component for $name {
+                fn wrap(self) -> AnyComponent {
+                    AnyComponent::$name(self)
+                }
+
+                #[allow(unreachable_patterns)]
+                fn unwrap(component: AnyComponent) -> Option<Self> {
+                    match component {
+                        AnyComponent::$name(component) => Some(component),
+                        _ => None,
+                    }
+                }
+
+                #[allow(unreachable_patterns)]
+                fn unwrap_ref(component: &AnyComponent) -> Option<&Self> {
+                    match component {
+                        AnyComponent::$name(component) => Some(component),
+                        _ => None,
+                    }
+                }
+
+                #[allow(unreachable_patterns)]
+                fn unwrap_mut(component: &mut AnyComponent) -> Option<&mut Self> {
+                    match component {
+                        AnyComponent::$name(component) => Some(component),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+components!(
+    Fighter, Ai, Item, Equipment, Gold, Inventory, Loot, Stack, Charges, Launcher, Spawned
+);
+
+// components stay with their object rather than in per-type columns, because
+// carried items are objects outside the entity store; an object holds a
+// handful of them, so the linear search is cheap
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Components {
+    components: Vec<AnyComponent>,
+}
+
+impl Components {
+    pub fn get<T: Component>(&self) -> Option<&T> {
+        self.components.iter().find_map(T::unwrap_ref)
+    }
+
+    pub fn get_mut<T: Component>(&mut self) -> Option<&mut T> {
+        self.components.iter_mut().find_map(T::unwrap_mut)
+    }
+
+    pub fn has<T: Component>(&self) -> bool {
+        self.get::<T>().is_some()
+    }
+
+    pub fn insert<T: Component>(&mut self, component: T) {
+        match self.get_mut::<T>() {
+            Some(existing) => *existing = component,
+            None => self.components.push(component.wrap()),
+        }
+    }
+
+    pub fn remove<T: Component>(&mut self) -> Option<T> {
+        let index = self
+            .components
+            .iter()
+            .position(|component| T::unwrap_ref(component).is_some())?;
+        T::unwrap(self.components.remove(index))
+    }
+}
@@ -20,6 +20,7 @@ pub const MSG_HEIGHT: usize = PANEL_HEIGHT as usize - 1;
 pub const INVENTORY_WIDTH: i32 = 50;
 pub const LEVEL_SCREEN_WIDTH: i32 = 40;
 pub const CHARACTER_SCREEN_WIDTH: i32 = 30;
+pub const EQUIPMENT_SCREEN_WIDTH: i32 = 50;
 
 // Spell/Item parameters
 pub const HEAL_AMOUNT: i32 = 40;
@@ -31,17 +32,82 @@ pub const FREEZE_RANGE: i32 = 10;
 pub const FREEZE_NUM_TURNS: i32 = 10;
 pub const FIREBALL_RADIUS: i32 = 3;
 pub const FIREBALL_DAMAGE: i32 = 25;
+pub const FIREBALL_RANGE: i32 = 10;
 pub const BLINK_RADIUS: i32 = 100;
+pub const CHARM_RANGE: i32 = 6;
+pub const ITEMS_FILE: &str = "items.json";
+pub const INVENTORY_SIZE: usize = 26;
+pub const MAX_STACK_SIZE: i32 = 20;
+pub const NOTE_MAX_LENGTH: usize = 20;
+pub const WAND_BURST_RADIUS: i32 = 3;
+pub const WAND_BURST_DAMAGE: i32 = 6;
+pub const RECHARGE_BREAK_CHANCE: i32 = 30;
+pub const THROW_RANGE: i32 = 6;
+pub const AMMO_BREAK_CHANCE: i32 = 20;
+pub const CONFUSED_AIM_SPREAD: i32 = 2;
+pub const OFF_HAND_POWER_PERCENT: i32 = 50;
+pub const AFFIXES_FILE: &str = "affixes.json";
+pub const MAGIC_ITEM_CHANCE: &[(u32, u32)] = &[(1, 15), (5, 25), (9, 30)];
+pub const RARE_ITEM_CHANCE: &[(u32, u32)] = &[(2, 3), (6, 8), (9, 12)];
+pub const ARTIFACT_ITEM_CHANCE: &[(u32, u32)] = &[(6, 1), (9, 2)];
+pub const ARTIFACT_AFFIX_MULTIPLIER: i32 = 2;
+pub const CURSED_ITEM_CHANCE: i32 = 10;
+pub const CURSE_PENALTY: i32 = 1;
+
+// Monster parameters
+pub const MONSTERS_FILE: &str = "monsters.json";
+pub const RANGED_KEEP_DISTANCE: f32 = 3.0;
+pub const CASTER_COOLDOWN: i32 = 3;
+pub const MONSTER_HEAL_AMOUNT: i32 = 10;
+pub const HEAL_ALLY_RANGE: i32 = 8;
+pub const MONSTER_BLINK_RANGE: i32 = 8;
+pub const MONSTER_BLINK_TRIES: i32 = 20;
+
+pub const MONSTER_HP_SCALING: i32 = 10;
+pub const MONSTER_XP_SCALING: i32 = 10;
+pub const MONSTER_POWER_LEVELS: i32 = 2;
+pub const MONSTER_DEFENSE_LEVELS: i32 = 4;
+pub const MONSTER_CARRY_LIMIT: usize = 3;
+
+// Elite parameters
+pub const ELITE_CHANCE: &[(u32, u32)] = &[(2, 5), (6, 12), (9, 15)];
+pub const CHAMPION_CHANCE: &[(u32, u32)] = &[(4, 1), (8, 4)];
+pub const ELITE_XP_MULTIPLIER: i32 = 2;
+pub const CHAMPION_XP_MULTIPLIER: i32 = 3;
+
+// Pack parameters
+pub const PACK_MIN_MEMBERS: i32 = 2;
+pub const PACK_MAX_MEMBERS: i32 = 3;
+pub const PACK_ENGAGE_COUNT: usize = 3;
+pub const PACK_GATHER_RADIUS: f32 = 4.0;
+pub const PACK_FLEE_TURNS: i32 = 8;
+
+// Summoner parameters
+pub const RAISE_DEAD_RANGE: i32 = 6;
+pub const RAISE_DEAD_COOLDOWN: i32 = 4;
+pub const NEST_SPAWN_INTERVAL: i32 = 5;
+pub const NEST_MAX_SPAWNS: usize = 4;
+
+// Boss parameters
+pub const BOSS_MINION_COUNT: i32 = 3;
+pub const BOSS_ENRAGE_POWER: i32 = 4;
+pub const BOSS_TELEPORT_COOLDOWN: i32 = 5;
+pub const BOSS_MIN_LOOT: i32 = 2;
+pub const BOSS_MAX_LOOT: i32 = 4;
+
+// Companion parameters
+pub const COMPANION_FOLLOW_DISTANCE: f32 = 2.0;
+pub const COMPANION_LEASH: f32 = 8.0;
+pub const COMPANION_UNLOCK_COST: i32 = 300;
 
 // Map generation parameters
 pub const ROOM_MAX_SIZE: i32 = 10;
 pub const ROOM_MIN_SIZE: i32 = 6;
 pub const MAX_ROOMS: i32 = 30;
-pub const MAX_ROOM_MONSTERS: i32 = 3;
-pub const MAX_ROOM_ITEMS: i32 = 2;
+pub const MAX_ROOM_MONSTERS: &[(u32, u32)] = &[(1, 2), (4, 3), (6, 5)];
+pub const MAX_ROOM_ITEMS: &[(u32, u32)] = &[(1, 1), (4, 2), (8, 3)];
 
 // Player/Game parameters
-pub const PLAYER: usize = 0;
 pub const LEVEL_UP_BASE: i32 = 200;
 pub const LEVEL_UP_FACTOR: i32 = 150;
 
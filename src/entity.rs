@@ -0,0 +1,230 @@
+use crate::component::Component;
+use crate::mut_two;
+use crate::object::Object;
+use serde::{Deserialize, Serialize};
+use std::ops::{Index, IndexMut};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EntityId {
+    index: usize,
+    generation: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    generation: u32,
+    object: Option<Object>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Objects {
+    entries: Vec<Entry>,
+    free: Vec<usize>,
+    player: EntityId,
+}
+
+impl Objects {
+    pub fn new(player: Object) -> Self {
+        let mut objects = Objects {
+            entries: vec![],
+            free: vec![],
+            player: EntityId {
+                index: 0,
+                generation: 0,
+            },
+        };
+        objects.player = objects.insert(player);
+        objects
+    }
+
+    pub fn player_id(&self) -> EntityId {
+        self.player
+    }
+
+    pub fn player(&self) -> &Object {
+        &self[self.player]
+    }
+
+    pub fn player_mut(&mut self) -> &mut Object {
+        let player = self.player;
+        &mut self[player]
+    }
+
+    pub fn insert(&mut self, object: Object) -> EntityId {
+        match self.free.pop() {
+            Some(index) => {
+                let entry = &mut self.entries[index];
+                entry.object = Some(object);
+                EntityId {
+                    index,
+                    generation: entry.generation,
+                }
+            }
+            None => {
+                self.entries.push(Entry {
+                    generation: 0,
+                    object: Some(object),
+                });
+                EntityId {
+                    index: self.entries.len() - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    pub fn remove(&mut self, id: EntityId) -> Option<Object> {
+        if !self.contains(id) {
+            return None;
+        }
+        // bumping the generation invalidates every id still pointing at this slot
+        let entry = &mut self.entries[id.index];
+        entry.generation += 1;
+        self.free.push(id.index);
+        entry.object.take()
+    }
+
+    pub fn retain<F: FnMut(EntityId, &Object) -> bool>(&mut self, mut keep: F) {
+        for id in self.ids() {
+            if !keep(id, &self[id]) {
+                self.remove(id);
+            }
+        }
+    }
+
+    pub fn contains(&self, id: EntityId) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&Object> {
+        self.entries
+            .get(id.index)
+            .filter(|entry| entry.generation == id.generation)
+            .and_then(|entry| entry.object.as_ref())
+    }
+
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut Object> {
+        self.entries
+            .get_mut(id.index)
+            .filter(|entry| entry.generation == id.generation)
+            .and_then(|entry| entry.object.as_mut())
+    }
+
+    pub fn get_two_mut(&mut self, first: EntityId, second: EntityId) -> (&mut Object, &mut Object) {
+        assert!(self.contains(first) && self.contains(second));
+        let (first, second) = mut_two(first.index, second.index, &mut self.entries);
+        (
+            first.object.as_mut().unwrap(),
+            second.object.as_mut().unwrap(),
+        )
+    }
+
+    pub fn ids(&self) -> Vec<EntityId> {
+        self.entries().map(|(id, _)| id).collect()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (EntityId, &Object)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                entry.object.as_ref().map(|object| {
+                    let id = EntityId {
+                        index,
+                        generation: entry.generation,
+                    };
+                    (id, object)
+                })
+            })
+    }
+
+    pub fn entries_mut(&mut self) -> impl Iterator<Item = (EntityId, &mut Object)> {
+        self.entries
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let generation = entry.generation;
+                entry
+                    .object
+                    .as_mut()
+                    .map(|object| (EntityId { index, generation }, object))
+            })
+    }
+
+    // still visits every object; columns keyed by EntityId would avoid that
+    // once carried items become entities of their own
+    pub fn query<T: Component>(&self) -> impl Iterator<Item = (EntityId, &Object, &T)> {
+        self.entries()
+            .filter_map(|(id, object)| object.get::<T>().map(|component| (id, object, component)))
+    }
+
+    pub fn position<P: Fn(&Object) -> bool>(&self, predicate: P) -> Option<EntityId> {
+        self.entries()
+            .find(|&(_, object)| predicate(object))
+            .map(|(id, _)| id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Object> {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.object.as_ref())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Object> {
+        self.entries
+            .iter_mut()
+            .filter_map(|entry| entry.object.as_mut())
+    }
+}
+
+impl Index<EntityId> for Objects {
+    type Output = Object;
+
+    fn index(&self, id: EntityId) -> &Object {
+        self.get(id)
+            .unwrap_or_else(|| panic!("stale entity id {:?}", id))
+    }
+}
+
+impl IndexMut<EntityId> for Objects {
+    fn index_mut(&mut self, id: EntityId) -> &mut Object {
+        self.get_mut(id)
+            .unwrap_or_else(|| panic!("stale entity id {:?}", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tcod::colors::WHITE;
+
+    fn object(name: &str) -> Object {
+        Object::new(0, 0, '@', WHITE, name, false)
+    }
+
+    #[test]
+    fn removed_ids_no_longer_resolve() {
+        let mut objects = Objects::new(object("player"));
+        let orc = objects.insert(object("orc"));
+        assert_eq!(objects.remove(orc).map(|orc| orc.name), Some("orc".into()));
+        assert!(objects.get(orc).is_none());
+        assert!(objects.remove(orc).is_none());
+    }
+
+    #[test]
+    fn reused_slots_get_a_new_generation() {
+        let mut objects = Objects::new(object("player"));
+        let orc = objects.insert(object("orc"));
+        let troll = objects.insert(object("troll"));
+        objects.remove(orc);
+
+        let goblin = objects.insert(object("goblin"));
+        assert_eq!(goblin.index, orc.index);
+        assert_eq!(goblin.generation, orc.generation + 1);
+        assert!(objects.get(orc).is_none());
+        assert_eq!(objects[goblin].name, "goblin");
+        assert_eq!(objects[troll].name, "troll");
+        assert_eq!(objects.entries.len(), 3);
+        assert!(objects.free.is_empty());
+    }
+}
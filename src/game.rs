@@ -1,11 +1,22 @@
 use crate::ai::{ai_take_turn, move_by};
 use crate::constants::*;
-use crate::item::{drop_item, item_spawner_menu, spawn_item_at_player, use_item};
-use crate::map::{Map, make_map};
+use crate::entity::{EntityId, Objects};
+use crate::identify::Identification;
+use crate::item::{
+    break_wand, drop_item, fire_launcher, item_spawner_menu, spawn_item_at_player, target_monster,
+    throw_item, use_item,
+};
+use crate::map::{Map, free_tile_near, is_blocked, make_map};
 use crate::meta::PermanentUpgrades;
-use crate::mut_two;
-use crate::object::{DeathCallback, Equipment, Fighter, Item, Object, Slot, pick_item_up};
-use crate::ui::{inventory_menu, menu, msgbox, render_all};
+use crate::object::{
+    Ai, DeathCallback, Equipment, Faction, Fighter, Gold, Grip, Item, Object, Order, Slot,
+    grant_xp, pick_item_up,
+};
+use crate::status::{StatusKind, tick_status_effects};
+use crate::ui::{
+    equipment_screen, inventory_menu, menu, msgbox, quantity_menu, render_all, text_input,
+};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fs::File;
@@ -22,17 +33,23 @@ pub struct Tcod {
     pub fov: FovMap,
     pub key: Key,
     pub mouse: Mouse,
+    pub projectile: Option<((i32, i32), char, Color)>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Game {
     pub map: Map,
     pub messages: Messages,
-    pub inventory: Vec<Object>,
     pub dungeon_level: u32,
+    #[serde(default)]
+    pub gold: i32,
+    #[serde(default)]
+    pub identification: Identification,
+    #[serde(skip)]
+    pub spawn_queue: Vec<Object>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct Messages {
     pub messages: Vec<(String, Color)>,
 }
@@ -58,37 +75,52 @@ enum PlayerAction {
     Exit,
 }
 
-pub fn new_game(tcod: &mut Tcod, upgrades: &PermanentUpgrades) -> (Game, Vec<Object>) {
+pub fn new_game(tcod: &mut Tcod, upgrades: &PermanentUpgrades) -> (Game, Objects) {
     let mut player = Object::new(0, 0, '@', WHITE, "player", true);
     player.alive = true;
-    player.fighter = Some(Fighter {
+    player.faction = Faction::Player;
+    player.insert(Fighter {
         base_max_hp: 100 + upgrades.bonus_hp,
         hp: 100 + upgrades.bonus_hp,
         base_defense: 1 + upgrades.bonus_defense,
         base_power: 2 + upgrades.bonus_power,
         xp: 0,
         on_death: DeathCallback::Player,
+        on_hit: None,
     });
 
-    let mut objects = vec![player];
+    let mut objects = Objects::new(player);
 
     let mut game = Game {
-        map: make_map(&mut objects),
+        map: make_map(&mut objects, 1),
         messages: Messages::new(),
-        inventory: vec![],
         dungeon_level: 1,
+        gold: 0,
+        identification: Identification::new(),
+        spawn_queue: vec![],
     };
 
+    if upgrades.companion {
+        let (player_x, player_y) = objects.player().pos();
+        if let Some((x, y)) = free_tile_near(player_x, player_y, &game.map, &objects) {
+            objects.insert(create_hound(x, y));
+        }
+    }
+
     let mut dagger = Object::new(0, 0, '-', SKY, "dagger", false);
-    dagger.item = Some(Item::Sword);
-    dagger.equipment = Some(Equipment {
+    dagger.insert(Item::Sword);
+    dagger.insert(Equipment {
         equipped: true,
-        slot: Slot::LeftHand,
+        slot: Slot::RightHand,
         max_hp_bonus: 0,
         defense_bonus: 0,
         power_bonus: 2,
+        fire_damage: 0,
+        grip: Grip::OneHanded,
+        cursed: false,
+        curse_known: true,
     });
-    game.inventory.push(dagger);
+    objects.player_mut().inventory_mut().push(dagger);
 
     initialise_fov(tcod, &game.map);
 
@@ -100,7 +132,26 @@ pub fn new_game(tcod: &mut Tcod, upgrades: &PermanentUpgrades) -> (Game, Vec<Obj
     (game, objects)
 }
 
-pub fn play_game(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<Object>) {
+fn create_hound(x: i32, y: i32) -> Object {
+    let mut hound = Object::new(x, y, 'd', LIGHT_AMBER, "hound", true);
+    hound.alive = true;
+    hound.faction = Faction::Player;
+    hound.insert(Fighter {
+        base_max_hp: 25,
+        hp: 25,
+        base_defense: 1,
+        base_power: 3,
+        xp: 0,
+        on_death: DeathCallback::Monster,
+        on_hit: None,
+    });
+    hound.insert(Ai::Companion {
+        order: Order::Follow,
+    });
+    hound
+}
+
+pub fn play_game(tcod: &mut Tcod, game: &mut Game, objects: &mut Objects) {
     let mut previous_player_position = (-1, -1);
 
     while !tcod.root.window_closed() {
@@ -112,42 +163,68 @@ pub fn play_game(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<Object>) {
             _ => tcod.key = Default::default(),
         }
 
-        let fov_recompute = previous_player_position != (objects[PLAYER].pos());
-        render_all(tcod, game, &objects, fov_recompute);
+        let fov_recompute = previous_player_position != (objects.player().pos());
+        render_all(tcod, game, objects, fov_recompute);
 
         tcod.root.flush();
 
         level_up(tcod, game, objects);
 
-        previous_player_position = objects[PLAYER].pos();
+        previous_player_position = objects.player().pos();
         let player_action = handle_keys(tcod, game, objects);
         if player_action == PlayerAction::Exit {
             save_game(game, objects).unwrap();
             break;
         }
 
-        if objects[PLAYER].alive && player_action != PlayerAction::DidntTakeTurn {
-            for id in 0..objects.len() {
-                if objects[id].ai.is_some() {
+        if objects.player().alive && player_action != PlayerAction::DidntTakeTurn {
+            // monsters closest to the player act first, so packs don't trip over each other
+            let mut ids: Vec<EntityId> = objects.query::<Ai>().map(|(id, _, _)| id).collect();
+            ids.sort_by(|&a, &b| {
+                let player = objects.player();
+                player
+                    .distance_to(&objects[a])
+                    .partial_cmp(&player.distance_to(&objects[b]))
+                    .unwrap()
+            });
+            for id in ids {
+                if objects[id].has::<Ai>() {
                     ai_take_turn(id, tcod, game, objects);
                 }
             }
+            for object in objects.iter_mut() {
+                tick_status_effects(object, game);
+            }
+            spawn_queued(game, objects);
         }
     }
 }
 
-pub fn save_game(game: &Game, objects: &[Object]) -> Result<(), Box<dyn Error>> {
+fn spawn_queued(game: &mut Game, objects: &mut Objects) {
+    for mut object in game.spawn_queue.drain(..) {
+        let (x, y) = object.pos();
+        if object.blocks && is_blocked(x, y, &game.map, objects) {
+            match free_tile_near(x, y, &game.map, objects) {
+                Some((x, y)) => object.set_pos(x, y),
+                None => continue,
+            }
+        }
+        objects.insert(object);
+    }
+}
+
+pub fn save_game(game: &Game, objects: &Objects) -> Result<(), Box<dyn Error>> {
     let save_data = serde_json::to_string(&(game, objects))?;
     let mut file = File::create("savegame")?;
     file.write_all(save_data.as_bytes())?;
     Ok(())
 }
 
-pub fn load_game() -> Result<(Game, Vec<Object>), Box<dyn Error>> {
+pub fn load_game() -> Result<(Game, Objects), Box<dyn Error>> {
     let mut json_save_state = String::new();
     let mut file = File::open("savegame")?;
     file.read_to_string(&mut json_save_state)?;
-    let result = serde_json::from_str::<(Game, Vec<Object>)>(&json_save_state)?;
+    let result = serde_json::from_str::<(Game, Objects)>(&json_save_state)?;
     Ok(result)
 }
 
@@ -166,12 +243,20 @@ pub fn initialise_fov(tcod: &mut Tcod, map: &Map) {
     tcod.con.clear();
 }
 
-fn handle_keys(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<Object>) -> PlayerAction {
+fn handle_keys(tcod: &mut Tcod, game: &mut Game, objects: &mut Objects) -> PlayerAction {
     use PlayerAction::*;
     use tcod::input::Key;
     use tcod::input::KeyCode::*;
 
-    let player_alive = objects[PLAYER].alive;
+    let player_alive = objects.player().alive;
+    let takes_turn = matches!(tcod.key.code, Up | Down | Left | Right)
+        || (tcod.key.code == Text && matches!(tcod.key.text(), "b" | "T" | "f" | "t"));
+    if player_alive && takes_turn && objects.player().has_status(StatusKind::Frozen) {
+        game.messages
+            .add("You are frozen solid and cannot act!", LIGHT_BLUE);
+        return TookTurn;
+    }
+
     match (tcod.key, tcod.key.text(), player_alive) {
         (
             Key {
@@ -205,8 +290,7 @@ fn handle_keys(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<Object>) -> P
         }
         (Key { code: Text, .. }, "g", true) => {
             let item_id = objects
-                .iter()
-                .position(|object| object.pos() == objects[PLAYER].pos() && object.item.is_some());
+                .position(|object| object.pos() == objects.player().pos() && object.has::<Item>());
             if let Some(item_id) = item_id {
                 pick_item_up(item_id, game, objects);
             }
@@ -214,7 +298,8 @@ fn handle_keys(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<Object>) -> P
         }
         (Key { code: Text, .. }, "i", true) => {
             let inventory_index = inventory_menu(
-                &game.inventory,
+                objects.player().inventory(),
+                &game.identification,
                 "Press the key next to an item to use it, or any other to cancel.\n",
                 &mut tcod.root,
             );
@@ -223,17 +308,73 @@ fn handle_keys(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<Object>) -> P
             }
             DidntTakeTurn
         }
+        (Key { code: Text, .. }, "e", true) => {
+            equipment_screen(objects.player(), &game.identification, &mut tcod.root);
+            DidntTakeTurn
+        }
+        (Key { code: Text, .. }, "n", true) => {
+            name_item(tcod, game, objects);
+            DidntTakeTurn
+        }
         (Key { code: Text, .. }, "d", true) => {
             let inventory_index = inventory_menu(
-                &game.inventory,
+                objects.player().inventory(),
+                &game.identification,
                 "Press the key next to an item to drop it, or any other to cancel.\n'",
                 &mut tcod.root,
             );
             if let Some(inventory_index) = inventory_index {
-                drop_item(inventory_index, game, objects);
+                let count = objects.player().inventory()[inventory_index].count();
+                let amount = if count > 1 {
+                    quantity_menu("Drop how many?\n", count, &mut tcod.root)
+                } else {
+                    Some(1)
+                };
+                if let Some(amount) = amount {
+                    drop_item(inventory_index, amount, game, objects);
+                }
             }
             DidntTakeTurn
         }
+        (Key { code: Text, .. }, "b", true) => {
+            if break_wand(tcod, game, objects) {
+                TookTurn
+            } else {
+                DidntTakeTurn
+            }
+        }
+        (Key { code: Text, .. }, "T", true) => {
+            let inventory_index = inventory_menu(
+                objects.player().inventory(),
+                &game.identification,
+                "Press the key next to an item to throw it, or any other to cancel.\n",
+                &mut tcod.root,
+            );
+            match inventory_index {
+                Some(inventory_index) if throw_item(inventory_index, tcod, game, objects) => {
+                    TookTurn
+                }
+                _ => DidntTakeTurn,
+            }
+        }
+        (Key { code: Text, .. }, "f", true) => {
+            if fire_launcher(tcod, game, objects) {
+                TookTurn
+            } else {
+                DidntTakeTurn
+            }
+        }
+        (Key { code: Text, .. }, "t", true) => {
+            if tame(game, objects) {
+                TookTurn
+            } else {
+                DidntTakeTurn
+            }
+        }
+        (Key { code: Text, .. }, "o", true) => {
+            give_order(tcod, game, objects);
+            DidntTakeTurn
+        }
         (Key { code: Text, .. }, "=", true) => {
             if let Some(item_type) = item_spawner_menu(&mut tcod.root) {
                 spawn_item_at_player(game, objects, item_type);
@@ -243,7 +384,7 @@ fn handle_keys(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<Object>) -> P
         (Key { code: Text, .. }, "<", true) => {
             let player_on_stairs = objects
                 .iter()
-                .any(|object| object.pos() == objects[PLAYER].pos() && object.name == "stairs");
+                .any(|object| object.pos() == objects.player().pos() && object.name == "stairs");
             if player_on_stairs {
                 next_level(tcod, game, objects);
             }
@@ -256,10 +397,10 @@ fn handle_keys(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<Object>) -> P
             _,
             true,
         ) => {
-            let player = &objects[PLAYER];
+            let player = objects.player();
             let level = player.level;
             let level_up_xp = LEVEL_UP_BASE + player.level * LEVEL_UP_FACTOR;
-            if let Some(fighter) = player.fighter.as_ref() {
+            if let Some(fighter) = player.get::<Fighter>() {
                 let msg = format!(
                     "Character information
 
@@ -273,9 +414,9 @@ Defense: {}",
                     level,
                     fighter.xp,
                     level_up_xp,
-                    player.max_hp(game),
-                    player.power(game),
-                    player.defense(game),
+                    player.max_hp(),
+                    player.power(),
+                    player.defense(),
                 );
                 msgbox(&msg, CHARACTER_SCREEN_WIDTH, &mut tcod.root);
             }
@@ -285,32 +426,96 @@ Defense: {}",
     }
 }
 
-fn player_move_or_attack(dx: i32, dy: i32, game: &mut Game, objects: &mut [Object]) {
-    let x = objects[PLAYER].x + dx;
-    let y = objects[PLAYER].y + dy;
+fn name_item(tcod: &mut Tcod, game: &mut Game, objects: &Objects) {
+    let inventory = objects.player().inventory();
+    let inventory_index = inventory_menu(
+        inventory,
+        &game.identification,
+        "Press the key next to an item to name it, or any other to cancel.\n",
+        &mut tcod.root,
+    );
+    let Some(item) = inventory_index.map(|index| &inventory[index]) else {
+        return;
+    };
+    match item.get::<Item>() {
+        Some(&kind) if !game.identification.is_identified(kind) => {
+            let header = format!("Call the {}:", game.identification.name(item));
+            if let Some(note) = text_input(&header, INVENTORY_WIDTH, &mut tcod.root) {
+                game.identification.set_note(kind, note);
+            }
+        }
+        _ => game
+            .messages
+            .add(format!("You already know the {}.", item.name), WHITE),
+    }
+}
+
+fn pick_up_gold(game: &mut Game, objects: &mut Objects) {
+    let player_pos = objects.player().pos();
+    let gold_ids: Vec<_> = objects
+        .query::<Gold>()
+        .filter(|(_, object, _)| object.pos() == player_pos)
+        .map(|(id, _, _)| id)
+        .collect();
+    for id in gold_ids {
+        if let Some(pile) = objects.remove(id) {
+            let amount = pile.get::<Gold>().map_or(0, |gold| gold.amount);
+            game.gold += amount;
+            game.messages
+                .add(format!("You pick up {} gold.", amount), GOLD);
+        }
+    }
+}
+
+fn player_move_or_attack(dx: i32, dy: i32, game: &mut Game, objects: &mut Objects) {
+    let confused = objects.player().has_status(StatusKind::Confused);
+    let (dx, dy) = if confused {
+        (
+            rand::rng().random_range(-1..2),
+            rand::rng().random_range(-1..2),
+        )
+    } else {
+        (dx, dy)
+    };
+
+    let x = objects.player().x + dx;
+    let y = objects.player().y + dy;
 
-    let target_id = objects
-        .iter()
-        .position(|object| object.fighter.is_some() && object.pos() == (x, y));
+    let player_id = objects.player_id();
+    let target_id = objects.position(|object| object.has::<Fighter>() && object.pos() == (x, y));
 
     match target_id {
+        Some(target_id) if target_id == player_id => {}
+        // only companions make way; neutral creatures are fair game
+        Some(target_id) if !confused && objects[target_id].faction == Faction::Player => {
+            let (player_x, player_y) = objects.player().pos();
+            objects[target_id].set_pos(player_x, player_y);
+            objects.player_mut().set_pos(x, y);
+            game.messages.add(
+                format!("You swap places with {}.", objects[target_id].name),
+                WHITE,
+            );
+        }
         Some(target_id) => {
-            let (player, target) = mut_two(PLAYER, target_id, objects);
-            player.attack(target, game);
+            let (player, target) = objects.get_two_mut(objects.player_id(), target_id);
+            if let Some(xp) = player.attack(target, game) {
+                grant_xp(objects.player_id(), xp, game, objects);
+            }
         }
         None => {
-            move_by(PLAYER, dx, dy, &game.map, objects);
+            move_by(objects.player_id(), dx, dy, &game.map, objects);
+            pick_up_gold(game, objects);
         }
     }
 }
 
-pub fn next_level(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<Object>) {
+pub fn next_level(tcod: &mut Tcod, game: &mut Game, objects: &mut Objects) {
     game.messages.add(
         "You take a moment to rest, and recover your strength.",
         VIOLET,
     );
-    let heal_hp = objects[PLAYER].max_hp(game) / 2;
-    objects[PLAYER].heal(heal_hp, game);
+    let heal_hp = objects.player().max_hp() / 2;
+    objects.player_mut().heal(heal_hp);
 
     game.messages.add(
         "After a rare moment of peace, you descend deeper into \
@@ -318,16 +523,130 @@ pub fn next_level(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<Object>) {
         RED,
     );
 
+    let player_id = objects.player_id();
+    for id in objects.ids() {
+        let object = &objects[id];
+        if object.alive && object.get::<Ai>() == Some(&Ai::Companion { order: Order::Stay }) {
+            game.messages
+                .add(format!("Your {} stays behind.", object.name), LIGHT_GREY);
+        }
+    }
+    // following companions keep their ids, so they stay the same entities on the new level
+    objects.retain(|id, object| {
+        id == player_id
+            || (object.alive
+                && object.is_companion()
+                && object.get::<Ai>() != Some(&Ai::Companion { order: Order::Stay }))
+    });
+    let companions: Vec<EntityId> = objects
+        .ids()
+        .into_iter()
+        .filter(|&id| id != player_id)
+        .collect();
+
     game.dungeon_level += 1;
-    game.map = make_map(objects);
+    game.map = make_map(objects, game.dungeon_level);
     initialise_fov(tcod, &game.map);
+
+    let (player_x, player_y) = objects.player().pos();
+    for id in companions {
+        match free_tile_near(player_x, player_y, &game.map, objects) {
+            Some((x, y)) => {
+                objects[id].set_pos(x, y);
+                objects[id].insert(Ai::Companion {
+                    order: Order::Follow,
+                });
+            }
+            None => {
+                objects.remove(id);
+            }
+        }
+    }
+}
+
+fn tame(game: &mut Game, objects: &mut Objects) -> bool {
+    let beast_id = objects.position(|object| {
+        object.alive
+            && object.faction == Faction::Beasts
+            && object.is_charmable()
+            && object.distance_to(objects.player()) < 2.0
+    });
+    let beast_id = match beast_id {
+        Some(beast_id) => beast_id,
+        None => {
+            game.messages
+                .add("There is no beast next to you to tame.", WHITE);
+            return false;
+        }
+    };
+
+    let wounded = objects[beast_id]
+        .get::<Fighter>()
+        .is_some_and(|f| f.hp * 2 <= objects[beast_id].max_hp());
+    if wounded && rand::random() {
+        objects[beast_id].faction = Faction::Player;
+        objects[beast_id].insert(Ai::Companion {
+            order: Order::Follow,
+        });
+        game.messages.add(
+            format!(
+                "The {} submits to you and becomes your companion!",
+                objects[beast_id].name
+            ),
+            LIGHT_GREEN,
+        );
+    } else {
+        game.messages.add(
+            format!(
+                "The {} snarls and refuses to be tamed.",
+                objects[beast_id].name
+            ),
+            RED,
+        );
+    }
+    true
+}
+
+fn give_order(tcod: &mut Tcod, game: &mut Game, objects: &mut Objects) {
+    if !objects.iter().any(|object| object.is_companion()) {
+        game.messages
+            .add("You have no companions to command.", WHITE);
+        return;
+    }
+
+    let choice = menu(
+        "Order your companions to:\n",
+        &["Follow me", "Stay here", "Attack a target"],
+        INVENTORY_WIDTH,
+        &mut tcod.root,
+    );
+    let order = match choice {
+        Some(0) => Order::Follow,
+        Some(1) => Order::Stay,
+        Some(2) => {
+            game.messages.add(
+                "Left-click an enemy to attack, or right-click to cancel.",
+                LIGHT_CYAN,
+            );
+            match target_monster(tcod, game, objects, None) {
+                Some(target_id) => Order::Attack(target_id),
+                None => return,
+            }
+        }
+        _ => return,
+    };
+
+    for object in objects.iter_mut().filter(|object| object.is_companion()) {
+        object.insert(Ai::Companion { order });
+    }
+    game.messages.add("Your companions obey.", LIGHT_GREEN);
 }
 
-fn level_up(tcod: &mut Tcod, game: &mut Game, objects: &mut [Object]) {
-    let player = &mut objects[PLAYER];
+fn level_up(tcod: &mut Tcod, game: &mut Game, objects: &mut Objects) {
+    let player = objects.player_mut();
     let level_up_xp = LEVEL_UP_BASE + player.level * LEVEL_UP_FACTOR;
 
-    if player.fighter.as_ref().map_or(0, |f| f.xp) >= level_up_xp {
+    if player.get::<Fighter>().map_or(0, |f| f.xp) >= level_up_xp {
         player.level += 1;
         game.messages.add(
             format!(
@@ -336,7 +655,7 @@ fn level_up(tcod: &mut Tcod, game: &mut Game, objects: &mut [Object]) {
             ),
             YELLOW,
         );
-        let fighter = player.fighter.as_mut().unwrap();
+        let fighter = player.get_mut::<Fighter>().unwrap();
         let mut choice = None;
         while choice.is_none() {
             choice = menu(
@@ -0,0 +1,283 @@
+use crate::item::item_templates;
+use crate::object::{Charges, Equipment, Item, Object, Stack, plural};
+use rand::Rng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+const POTION_LOOKS: &[&str] = &[
+    "murky", "bubbling", "smoky", "cloudy", "fizzing", "glowing", "viscous", "golden", "speckled",
+    "oily", "milky", "swirling",
+];
+const WAND_MATERIALS: &[&str] = &[
+    "oak", "ebony", "iron", "bone", "crystal", "copper", "willow", "glass", "ivory", "brass",
+];
+const SCROLL_SYLLABLES: &[&str] = &[
+    "xyz", "zy", "ab", "ra", "ca", "dab", "ka", "lor", "em", "nu", "ith", "pho", "ver", "gol",
+    "thu", "ek",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Appearance {
+    Potion,
+    Scroll,
+    Wand,
+    Staff,
+}
+
+// what the player knows about item kinds this run; kinds without an appearance are always known
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Identification {
+    #[serde(with = "pairs")]
+    appearances: HashMap<Item, String>,
+    identified: HashSet<Item>,
+    #[serde(with = "pairs")]
+    notes: HashMap<Item, String>,
+}
+
+// json object keys must be strings, which wand kinds are not
+mod pairs {
+    use crate::object::Item;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::collections::HashMap;
+
+    pub fn serialize<S: Serializer>(
+        map: &HashMap<Item, String>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<Item, String>, D::Error> {
+        Vec::<(Item, String)>::deserialize(deserializer).map(|pairs| pairs.into_iter().collect())
+    }
+}
+
+impl Identification {
+    pub fn new() -> Self {
+        let mut rng = rand::rng();
+        let mut potions = POTION_LOOKS.to_vec();
+        potions.shuffle(&mut rng);
+        let mut labels = HashSet::new();
+        let mut materials = WAND_MATERIALS.to_vec();
+        materials.shuffle(&mut rng);
+
+        let mut appearances = HashMap::new();
+        for template in item_templates() {
+            let appearance = match template.appearance {
+                Some(Appearance::Potion) => match potions.pop() {
+                    Some(look) => format!("{} potion", look),
+                    None => continue,
+                },
+                Some(Appearance::Scroll) => {
+                    let label = loop {
+                        let label: String = (0..rng.random_range(2..=3))
+                            .map(|_| *SCROLL_SYLLABLES.choose(&mut rng).unwrap())
+                            .collect();
+                        if labels.insert(label.clone()) {
+                            break label;
+                        }
+                    };
+                    format!("scroll labelled {}", label.to_uppercase())
+                }
+                Some(kind @ (Appearance::Wand | Appearance::Staff)) => match materials.pop() {
+                    Some(material) if kind == Appearance::Wand => format!("{} wand", material),
+                    Some(material) => format!("{} staff", material),
+                    None => continue,
+                },
+                None => continue,
+            };
+            appearances.insert(template.item, appearance);
+        }
+
+        Identification {
+            appearances,
+            identified: HashSet::new(),
+            notes: HashMap::new(),
+        }
+    }
+
+    pub fn is_identified(&self, item: Item) -> bool {
+        !self.appearances.contains_key(&item) || self.identified.contains(&item)
+    }
+
+    pub fn identify(&mut self, item: Item) -> bool {
+        !self.is_identified(item) && self.identified.insert(item)
+    }
+
+    pub fn set_note(&mut self, item: Item, note: String) {
+        if note.is_empty() {
+            self.notes.remove(&item);
+        } else {
+            self.notes.insert(item, note);
+        }
+    }
+
+    fn unknown_kind(&self, object: &Object) -> Option<Item> {
+        object
+            .get::<Item>()
+            .copied()
+            .filter(|&item| !self.is_identified(item))
+    }
+
+    // ammunition is never cursed, so there is nothing to find out about it
+    fn curse_unknown(object: &Object) -> bool {
+        !object.has::<Stack>() && object.get::<Equipment>().is_some_and(|e| !e.curse_known)
+    }
+
+    pub fn is_unknown(&self, object: &Object) -> bool {
+        self.unknown_kind(object).is_some() || Self::curse_unknown(object)
+    }
+
+    fn shown(&self, object: &Object, plural_form: bool) -> String {
+        let known_cursed = object
+            .get::<Equipment>()
+            .is_some_and(|e| e.cursed && e.curse_known);
+        if known_cursed {
+            return format!("cursed {}", object.name);
+        }
+        let Some(item) = self.unknown_kind(object) else {
+            return if plural_form {
+                plural(&object.name)
+            } else {
+                object.name.clone()
+            };
+        };
+        let appearance = &self.appearances[&item];
+        let appearance = if plural_form {
+            plural(appearance)
+        } else {
+            appearance.clone()
+        };
+        match self.notes.get(&item) {
+            Some(note) => format!("{} (called {})", appearance, note),
+            None => appearance,
+        }
+    }
+
+    pub fn name(&self, object: &Object) -> String {
+        self.shown(object, false)
+    }
+
+    pub fn display_name(&self, object: &Object) -> String {
+        let name = match object.count() {
+            1 => self.shown(object, false),
+            count => format!("{} {}", count, self.shown(object, true)),
+        };
+        match object.get::<Charges>() {
+            Some(charges) if self.unknown_kind(object).is_none() => {
+                format!("{} ({}/{})", name, charges.current, charges.max)
+            }
+            _ => name,
+        }
+    }
+
+    pub fn described(&self, object: &Object) -> String {
+        match object.count() {
+            1 => {
+                let name = self.shown(object, false);
+                let article = match name.chars().next() {
+                    Some('a' | 'e' | 'i' | 'o' | 'u') => "an",
+                    _ => "a",
+                };
+                format!("{} {}", article, name)
+            }
+            _ => self.display_name(object),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Messages;
+    use crate::item::{create_item, load_test_templates};
+    use crate::object::Stack;
+    use crate::spell::Spell;
+    use tcod::colors::VIOLET;
+
+    fn potions(count: i32) -> Object {
+        let mut potion = Object::new(0, 0, '!', VIOLET, "healing potion", false);
+        potion.insert(Item::Heal);
+        potion.insert(Stack { count });
+        potion
+    }
+
+    fn unknown_heal() -> Identification {
+        let mut identification = Identification::default();
+        identification
+            .appearances
+            .insert(Item::Heal, "murky potion".into());
+        identification
+    }
+
+    #[test]
+    fn unknown_items_show_their_appearance() {
+        let mut identification = unknown_heal();
+        assert_eq!(identification.described(&potions(1)), "a murky potion");
+        assert_eq!(identification.display_name(&potions(3)), "3 murky potions");
+
+        identification.set_note(Item::Heal, "red?".into());
+        assert_eq!(
+            identification.name(&potions(1)),
+            "murky potion (called red?)"
+        );
+
+        assert!(identification.identify(Item::Heal));
+        assert!(!identification.identify(Item::Heal));
+        assert_eq!(
+            identification.display_name(&potions(3)),
+            "3 healing potions"
+        );
+    }
+
+    #[test]
+    fn wand_charges_show_once_identified() {
+        let mut wand = Object::new(0, 0, '-', VIOLET, "wand of lightning", false);
+        wand.insert(Item::Wand(Spell::Lightning));
+        wand.insert(Charges {
+            current: 2,
+            max: 5,
+            recharged: false,
+        });
+        let mut identification = Identification::default();
+        identification
+            .appearances
+            .insert(Item::Wand(Spell::Lightning), "oak wand".into());
+        assert_eq!(identification.display_name(&wand), "oak wand");
+        assert_eq!(identification.described(&wand), "an oak wand");
+
+        let json = serde_json::to_string(&identification).unwrap();
+        let mut identification: Identification = serde_json::from_str(&json).unwrap();
+        identification.identify(Item::Wand(Spell::Lightning));
+        assert_eq!(
+            identification.display_name(&wand),
+            "wand of lightning (2/5)"
+        );
+    }
+
+    #[test]
+    fn curses_stay_hidden_until_found_out() {
+        load_test_templates();
+        let mut sword = create_item(Item::Sword, 0, 0);
+        let equipment = sword.get_mut::<Equipment>().unwrap();
+        equipment.power_bonus = -3;
+        equipment.cursed = true;
+        let identification = Identification::default();
+        assert!(identification.is_unknown(&sword));
+        assert_eq!(identification.described(&sword), "a sword");
+
+        let mut messages = Messages::new();
+        sword.equip(&mut messages);
+        assert!(!sword.dequip(&mut messages));
+        assert!(!identification.is_unknown(&sword));
+        assert_eq!(identification.described(&sword), "a cursed sword");
+    }
+
+    #[test]
+    fn scroll_labels_pluralise_the_scroll() {
+        assert_eq!(plural("scroll labelled XYZZY"), "scrolls labelled XYZZY");
+    }
+}
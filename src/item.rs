@@ -1,101 +1,242 @@
 use crate::constants::*;
+use crate::entity::{EntityId, Objects};
 use crate::game::{Game, Tcod};
-use crate::map::is_blocked;
-use crate::object::{Ai, Item, Object, Slot};
-use crate::ui::{menu, render_all};
+use crate::identify::Appearance;
+use crate::map::{check_depth_table, from_dungeon_level, is_blocked};
+use crate::object::{
+    Ai, Charges, Equipment, Faction, Fighter, Gold, Grip, Item, Launcher, Object, Slot, Stack,
+    grant_xp, plural,
+};
+use crate::projectile::{Projectile, trace_path};
+use crate::spell::{self, Spell};
+use crate::status::StatusKind;
+use crate::ui::{animate_projectile, menu, render_all};
+use rand::Rng;
+use serde::Deserialize;
+use std::cmp;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+use std::sync::OnceLock;
 use tcod::colors::*;
 use tcod::input::{self, Event};
 
-pub fn create_item(item_type: Item, x: i32, y: i32) -> Object {
-    match item_type {
-        Item::Heal => {
-            let mut object = Object::new(x, y, '#', LIGHT_YELLOW, "healing potion", false);
-            object.item = Some(Item::Heal);
-            object
-        }
-        Item::Lightning => {
-            let mut object =
-                Object::new(x, y, '#', LIGHT_YELLOW, "scroll of lightning bolt", false);
-            object.item = Some(Item::Lightning);
-            object
-        }
-        Item::Confuse => {
-            let mut object = Object::new(x, y, '#', LIGHT_YELLOW, "scroll of confusion", false);
-            object.item = Some(Item::Confuse);
-            object
-        }
-        Item::Fireball => {
-            let mut object = Object::new(x, y, '#', LIGHT_YELLOW, "scroll of fireball", false);
-            object.item = Some(Item::Fireball);
-            object
-        }
-        Item::Blink => {
-            let mut object = Object::new(x, y, '#', LIGHT_YELLOW, "scroll of blink", false);
-            object.item = Some(Item::Blink);
-            object
-        }
-        Item::Freeze => {
-            let mut object = Object::new(x, y, '#', LIGHT_YELLOW, "scroll of freeze", false);
-            object.item = Some(Item::Freeze);
-            object
-        }
-        Item::Sword => {
-            let mut object = Object::new(x, y, '/', SKY, "sword", false);
-            object.item = Some(Item::Sword);
-            object.equipment = Some(crate::object::Equipment {
-                slot: Slot::RightHand,
-                equipped: false,
-                max_hp_bonus: 0,
-                defense_bonus: 0,
-                power_bonus: 1000,
-            });
-            object
-        }
-        Item::Shield => {
-            let mut object = Object::new(x, y, '/', DARKER_ORANGE, "shield", false);
-            object.item = Some(Item::Shield);
-            object.equipment = Some(crate::object::Equipment {
-                slot: Slot::LeftHand,
-                equipped: false,
-                max_hp_bonus: 0,
-                defense_bonus: 1000,
-                power_bonus: 0,
-            });
-            object
-        }
+static ITEMS: OnceLock<Vec<ItemTemplate>> = OnceLock::new();
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemTemplate {
+    pub item: Item,
+    pub name: String,
+    pub glyph: char,
+    pub color: [u8; 3],
+    #[serde(default)]
+    pub spawn_weight: Vec<(u32, u32)>,
+    #[serde(default)]
+    pub equipment: Option<Equipment>,
+    #[serde(default)]
+    pub stackable: bool,
+    #[serde(default)]
+    pub appearance: Option<Appearance>,
+    #[serde(default)]
+    pub charges: Option<(i32, i32)>,
+    #[serde(default)]
+    pub weight: i32,
+    #[serde(default)]
+    pub launcher: Option<Launcher>,
+    #[serde(default)]
+    pub bundle: Option<(i32, i32)>,
+}
+
+impl ItemTemplate {
+    pub fn spawn_weight_at(&self, level: u32) -> u32 {
+        from_dungeon_level(&self.spawn_weight, level)
     }
 }
 
-pub fn item_spawner_menu(root: &mut tcod::console::Root) -> Option<Item> {
-    let options = vec![
-        "Healing Potion",
-        "Lightning Bolt Scroll",
-        "Freeze Scroll",
-        "Confusion Scroll",
-        "Fireball Scroll",
-        "Blink Scroll",
-    ];
+pub fn load_items(path: &str) -> Result<(), Box<dyn Error>> {
+    let mut json = String::new();
+    let mut file = File::open(path)?;
+    file.read_to_string(&mut json)?;
+    let templates = parse_items(&json)?;
+    let _ = ITEMS.set(templates);
+    Ok(())
+}
 
-    let item_types = vec![
-        Item::Heal,
-        Item::Lightning,
-        Item::Freeze,
-        Item::Confuse,
-        Item::Fireball,
-        Item::Blink,
-    ];
+pub fn parse_items(json: &str) -> Result<Vec<ItemTemplate>, Box<dyn Error>> {
+    let templates = serde_json::from_str::<Vec<ItemTemplate>>(json)?;
+    validate_items(&templates)?;
+    Ok(templates)
+}
 
-    let selected_index = menu("Choose an item to spawn:", &options, INVENTORY_WIDTH, root);
+fn validate_items(templates: &[ItemTemplate]) -> Result<(), String> {
+    for item in Item::ALL {
+        match templates
+            .iter()
+            .filter(|template| template.item == item)
+            .count()
+        {
+            0 => return Err(format!("item {:?} has no template", item)),
+            1 => {}
+            _ => return Err(format!("item {:?} is defined twice", item)),
+        }
+    }
 
-    if let Some(index) = selected_index {
-        Some(item_types[index])
-    } else {
-        None
+    let mut names = HashSet::new();
+    for template in templates {
+        if template.name.is_empty() {
+            return Err(format!("item {:?} has an empty name", template.item));
+        }
+        if !names.insert(template.name.as_str()) {
+            return Err(format!("item name '{}' is used twice", template.name));
+        }
+        check_depth_table(&template.spawn_weight).map_err(|e| {
+            format!(
+                "item '{}' has a bad spawn_weight table: {}",
+                template.name, e
+            )
+        })?;
+        match &template.equipment {
+            None if template.item.is_equipment() => {
+                return Err(format!("item '{}' is missing its equipment", template.name));
+            }
+            Some(_) if !template.item.is_equipment() => {
+                return Err(format!("item '{}' cannot be equipped", template.name));
+            }
+            Some(equipment) if template.stackable && equipment.slot != Slot::Quiver => {
+                return Err(format!("equipment '{}' cannot stack", template.name));
+            }
+            Some(equipment) if equipment.equipped => {
+                return Err(format!("item '{}' must not start equipped", template.name));
+            }
+            Some(equipment) if equipment.cursed => {
+                return Err(format!("item '{}' must not start cursed", template.name));
+            }
+            Some(equipment)
+                if equipment.grip != Grip::None && equipment.slot != Slot::RightHand =>
+            {
+                return Err(format!(
+                    "weapon '{}' must go in the right hand",
+                    template.name
+                ));
+            }
+            _ => {}
+        }
+        match template.charges {
+            None if matches!(template.item, Item::Wand(_)) => {
+                return Err(format!("wand '{}' is missing its charges", template.name));
+            }
+            Some(_) if !matches!(template.item, Item::Wand(_)) => {
+                return Err(format!("item '{}' cannot have charges", template.name));
+            }
+            Some(_) if template.stackable => {
+                return Err(format!("wand '{}' cannot stack", template.name));
+            }
+            Some((min, max)) if min < 1 || min > max => {
+                return Err(format!("wand '{}' has bad charges", template.name));
+            }
+            _ => {}
+        }
+        if template.weight < 0 {
+            return Err(format!("item '{}' has a negative weight", template.name));
+        }
+        match template.bundle {
+            Some(_) if !template.stackable => {
+                return Err(format!("item '{}' cannot come in bundles", template.name));
+            }
+            Some((min, max)) if min < 1 || min > max || max > MAX_STACK_SIZE => {
+                return Err(format!("item '{}' has a bad bundle size", template.name));
+            }
+            _ => {}
+        }
+        if let Some(launcher) = template.launcher {
+            if template.equipment.map(|e| e.slot) != Some(Slot::Ranged) {
+                return Err(format!(
+                    "launcher '{}' must go in the ranged slot",
+                    template.name
+                ));
+            }
+            let ammo = templates.iter().find(|ammo| ammo.item == launcher.ammo);
+            if ammo.and_then(|ammo| ammo.equipment).map(|e| e.slot) != Some(Slot::Quiver) {
+                return Err(format!(
+                    "launcher '{}' fires something that is not ammunition",
+                    template.name
+                ));
+            }
+            if launcher.range < 1 {
+                return Err(format!("launcher '{}' has a bad range", template.name));
+            }
+        }
     }
+    Ok(())
 }
 
-pub fn spawn_item_at_player(game: &mut Game, objects: &mut Vec<Object>, item_type: Item) {
-    let (player_x, player_y) = objects[PLAYER].pos();
+pub fn item_templates() -> &'static [ItemTemplate] {
+    ITEMS.get().expect("item templates were not loaded")
+}
+
+pub fn item_template(item: Item) -> &'static ItemTemplate {
+    item_templates()
+        .iter()
+        .find(|template| template.item == item)
+        .unwrap_or_else(|| panic!("item {:?} has no template", item))
+}
+
+pub fn create_item(item: Item, x: i32, y: i32) -> Object {
+    let template = item_template(item);
+    let [r, g, b] = template.color;
+    let mut object = Object::new(
+        x,
+        y,
+        template.glyph,
+        Color::new(r, g, b),
+        &template.name,
+        false,
+    );
+    object.insert(item);
+    if let Some(equipment) = template.equipment {
+        object.insert(equipment);
+    }
+    if template.stackable {
+        let count = template
+            .bundle
+            .map_or(1, |(min, max)| rand::rng().random_range(min..=max));
+        object.insert(Stack { count });
+    }
+    if let Some(launcher) = template.launcher {
+        object.insert(launcher);
+    }
+    if let Some((min, max)) = template.charges {
+        let max = rand::rng().random_range(min..=max);
+        object.insert(Charges {
+            current: max,
+            max,
+            recharged: false,
+        });
+    }
+    object.always_visible = true;
+    object
+}
+
+pub fn create_gold(amount: i32, x: i32, y: i32) -> Object {
+    let mut gold = Object::new(x, y, '$', GOLD, &format!("{} gold coins", amount), false);
+    gold.insert(Gold { amount });
+    gold.always_visible = true;
+    gold
+}
+
+pub fn item_spawner_menu(root: &mut tcod::console::Root) -> Option<Item> {
+    let options: Vec<_> = item_templates()
+        .iter()
+        .map(|template| template.name.as_str())
+        .collect();
+    let selected_index = menu("Choose an item to spawn:", &options, INVENTORY_WIDTH, root);
+    selected_index.map(|index| item_templates()[index].item)
+}
+
+pub fn spawn_item_at_player(game: &mut Game, objects: &mut Objects, item_type: Item) {
+    let (player_x, player_y) = objects.player().pos();
 
     let directions = vec![
         (0, 0),
@@ -113,10 +254,8 @@ pub fn spawn_item_at_player(game: &mut Game, objects: &mut Vec<Object>, item_typ
         let spawn_x = player_x + dx;
         let spawn_y = player_y + dy;
 
-        if spawn_x >= 0
-            && spawn_x < MAP_WIDTH
-            && spawn_y >= 0
-            && spawn_y < MAP_HEIGHT
+        if (0..MAP_WIDTH).contains(&spawn_x)
+            && (0..MAP_HEIGHT).contains(&spawn_y)
             && !is_blocked(spawn_x, spawn_y, &game.map, objects)
         {
             let item = create_item(item_type, spawn_x, spawn_y);
@@ -124,7 +263,7 @@ pub fn spawn_item_at_player(game: &mut Game, objects: &mut Vec<Object>, item_typ
                 format!("Spawned {} at ({}, {})", item.name, spawn_x, spawn_y),
                 LIGHT_CYAN,
             );
-            objects.push(item);
+            objects.insert(item);
             return;
         }
     }
@@ -132,15 +271,235 @@ pub fn spawn_item_at_player(game: &mut Game, objects: &mut Vec<Object>, item_typ
     game.messages.add("No valid position to spawn item!", RED);
 }
 
-pub fn drop_item(inventory_id: usize, game: &mut Game, objects: &mut Vec<Object>) {
-    let mut item = game.inventory.remove(inventory_id);
-    if item.equipment.is_some() {
+pub fn fits_in_inventory(inventory: &[Object], item: &Object) -> bool {
+    inventory.len() < INVENTORY_SIZE
+        || inventory.iter().any(|existing| {
+            existing.stacks_with(item) && existing.count() + item.count() <= MAX_STACK_SIZE
+        })
+}
+
+// tops up matching stacks first and only opens a new slot for what is left over
+pub fn add_to_inventory(inventory: &mut Vec<Object>, mut item: Object) -> usize {
+    for (index, existing) in inventory.iter_mut().enumerate() {
+        if !existing.stacks_with(&item) {
+            continue;
+        }
+        let moved = cmp::min(MAX_STACK_SIZE - existing.count(), item.count());
+        if moved <= 0 {
+            continue;
+        }
+        existing.set_count(existing.count() + moved);
+        item.set_count(item.count() - moved);
+        if item.count() == 0 {
+            return index;
+        }
+    }
+    inventory.push(item);
+    inventory.len() - 1
+}
+
+pub fn split_stack(inventory: &mut Vec<Object>, inventory_id: usize, amount: i32) -> Object {
+    let count = inventory[inventory_id].count();
+    if amount >= count {
+        return inventory.remove(inventory_id);
+    }
+    inventory[inventory_id].set_count(count - amount);
+    let mut split = inventory[inventory_id].clone();
+    split.set_count(amount);
+    // the rest of the stack stays in the slot
+    if let Some(equipment) = split.get_mut::<Equipment>() {
+        equipment.equipped = false;
+    }
+    split
+}
+
+pub fn drop_item(inventory_id: usize, amount: i32, game: &mut Game, objects: &mut Objects) {
+    let inventory = objects.player_mut().inventory_mut();
+    if inventory[inventory_id].is_stuck() {
+        inventory[inventory_id].dequip(&mut game.messages);
+        return;
+    }
+    let mut item = split_stack(objects.player_mut().inventory_mut(), inventory_id, amount);
+    if item.has::<Equipment>() {
         item.dequip(&mut game.messages);
     }
-    item.set_pos(objects[PLAYER].x, objects[PLAYER].y);
-    game.messages
-        .add(format!("You dropped a {}.", item.name), YELLOW);
-    objects.push(item);
+    item.set_pos(objects.player().x, objects.player().y);
+    game.messages.add(
+        format!("You dropped {}.", game.identification.described(&item)),
+        YELLOW,
+    );
+    objects.insert(item);
+}
+
+pub fn throw_item(
+    inventory_id: usize,
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+) -> bool {
+    let inventory = objects.player_mut().inventory_mut();
+    if inventory[inventory_id].is_stuck() {
+        inventory[inventory_id].dequip(&mut game.messages);
+        return false;
+    }
+    let name = game
+        .identification
+        .name(&objects.player().inventory()[inventory_id]);
+    game.messages.add(
+        format!(
+            "Left-click where to throw the {}, or right-click to cancel.",
+            name
+        ),
+        LIGHT_CYAN,
+    );
+    let Some(target) = target_tile(tcod, game, objects, Some(THROW_RANGE as f32)) else {
+        return false;
+    };
+    let target = confused_aim(target, game, objects);
+    let mut item = split_stack(objects.player_mut().inventory_mut(), inventory_id, 1);
+    if item.has::<Equipment>() {
+        item.dequip(&mut game.messages);
+    }
+    let weapon_power = item.get::<Equipment>().map_or(0, |e| e.power_bonus);
+    let damage = item
+        .get::<Item>()
+        .map_or(0, |&kind| item_template(kind).weight)
+        + weapon_power;
+    hurl(
+        item,
+        target,
+        damage,
+        Projectile::Thrown,
+        tcod,
+        game,
+        objects,
+    );
+    true
+}
+
+pub fn fire_launcher(tcod: &mut Tcod, game: &mut Game, objects: &mut Objects) -> bool {
+    let inventory = objects.player().inventory();
+    let launcher = get_equipped_in_slot(Slot::Ranged, inventory)
+        .and_then(|index| inventory[index].get::<Launcher>())
+        .copied();
+    let Some(launcher) = launcher else {
+        game.messages.add("You have nothing to fire with.", WHITE);
+        return false;
+    };
+    let ammo_id = get_equipped_in_slot(Slot::Quiver, inventory)
+        .filter(|&index| inventory[index].get::<Item>() == Some(&launcher.ammo));
+    let Some(ammo_id) = ammo_id else {
+        game.messages.add(
+            format!(
+                "You have no {} quivered.",
+                plural(&item_template(launcher.ammo).name)
+            ),
+            WHITE,
+        );
+        return false;
+    };
+
+    game.messages.add(
+        "Left-click a target to fire at, or right-click to cancel.",
+        LIGHT_CYAN,
+    );
+    let Some(target) = target_tile(tcod, game, objects, Some(launcher.range as f32)) else {
+        return false;
+    };
+    let target = confused_aim(target, game, objects);
+    let mut ammo = split_stack(objects.player_mut().inventory_mut(), ammo_id, 1);
+    if ammo.has::<Equipment>() {
+        ammo.dequip(&mut game.messages);
+    }
+    let damage = launcher.power + item_template(launcher.ammo).weight;
+    hurl(ammo, target, damage, Projectile::Arrow, tcod, game, objects);
+    true
+}
+
+fn confused_aim(target: (i32, i32), game: &mut Game, objects: &Objects) -> (i32, i32) {
+    if !objects.player().has_status(StatusKind::Confused) {
+        return target;
+    }
+    game.messages.add("You fumble your aim!", LIGHT_BLUE);
+    let mut rng = rand::rng();
+    let (x, y) = target;
+    (
+        (x + rng.random_range(-CONFUSED_AIM_SPREAD..=CONFUSED_AIM_SPREAD)).clamp(0, MAP_WIDTH - 1),
+        (y + rng.random_range(-CONFUSED_AIM_SPREAD..=CONFUSED_AIM_SPREAD)).clamp(0, MAP_HEIGHT - 1),
+    )
+}
+
+// flies the item along the line of fire and leaves it where it lands
+fn hurl(
+    mut item: Object,
+    target: (i32, i32),
+    damage: i32,
+    projectile: Projectile,
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+) {
+    let player_id = objects.player_id();
+    let origin = objects.player().pos();
+    let (path, hit) = trace_path(origin, target, &game.map, objects);
+    animate_projectile(tcod, game, objects, origin, &path, projectile);
+
+    let name = game.identification.name(&item);
+    let kind = item.get::<Item>().copied();
+    let hit = hit.filter(|&id| objects[id].has::<Fighter>());
+    if let Some(kind) =
+        kind.filter(|&kind| item_template(kind).appearance == Some(Appearance::Potion))
+    {
+        game.messages
+            .add(format!("The {} shatters!", name), LIGHT_BLUE);
+        if let Some(hit_id) = hit {
+            shatter(kind, hit_id, game, objects);
+        }
+        return;
+    }
+
+    if let Some(hit_id) = hit {
+        let damage = damage - objects[hit_id].defense();
+        if damage > 0 {
+            game.messages.add(
+                format!(
+                    "The {} hits the {} for {} hit points.",
+                    name, objects[hit_id].name, damage
+                ),
+                WHITE,
+            );
+            if let Some(xp) = objects[hit_id].take_damage(damage, game) {
+                grant_xp(player_id, xp, game, objects);
+            }
+        } else {
+            game.messages.add(
+                format!("The {} glances off the {}.", name, objects[hit_id].name),
+                WHITE,
+            );
+        }
+    }
+
+    let is_ammo = item.get::<Equipment>().map(|e| e.slot) == Some(Slot::Quiver);
+    if is_ammo && rand::rng().random_range(0..100) < AMMO_BREAK_CHANCE {
+        game.messages.add(format!("The {} breaks.", name), WHITE);
+        return;
+    }
+    let (x, y) = path.last().copied().unwrap_or(origin);
+    item.set_pos(x, y);
+    objects.insert(item);
+}
+
+fn shatter(kind: Item, target_id: EntityId, game: &mut Game, objects: &mut Objects) {
+    let Item::Heal = kind else {
+        return;
+    };
+    spell::heal(target_id, HEAL_AMOUNT, game, objects);
+    if game.identification.identify(kind) {
+        game.messages.add(
+            format!("That was a {}!", item_template(kind).name),
+            LIGHT_CYAN,
+        );
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -150,10 +509,10 @@ pub enum UseResult {
     UsedAndKept,
 }
 
-pub fn use_item(inventory_id: usize, tcod: &mut Tcod, game: &mut Game, objects: &mut [Object]) {
+pub fn use_item(inventory_id: usize, tcod: &mut Tcod, game: &mut Game, objects: &mut Objects) {
     use Item::*;
 
-    if let Some(item) = game.inventory[inventory_id].item {
+    if let Some(&item) = objects.player().inventory()[inventory_id].get::<Item>() {
         let on_use = match item {
             Heal => cast_heal,
             Lightning => cast_lightning,
@@ -161,12 +520,27 @@ pub fn use_item(inventory_id: usize, tcod: &mut Tcod, game: &mut Game, objects:
             Fireball => cast_fireball,
             Blink => cast_blink,
             Freeze => cast_freeze,
+            Charm => cast_charm,
+            Identify => cast_identify,
+            Recharge => cast_recharge,
+            RemoveCurse => cast_remove_curse,
+            Wand(_) => cast_wand,
             Sword => toggle_equipment,
             Shield => toggle_equipment,
+            Greatsword => toggle_equipment,
+            Bow | Crossbow | Arrow | Bolt => toggle_equipment,
+            Helmet | LeatherArmour | ChainMail | Cloak | Gauntlets | Boots => toggle_equipment,
+            ProtectionRing | StrengthRing | VitalityRing | Amulet => toggle_equipment,
         };
-        match on_use(inventory_id, tcod, game, objects) {
+        let name = objects.player().inventory()[inventory_id].name.clone();
+        let result = on_use(inventory_id, tcod, game, objects);
+        if result != UseResult::Cancelled && game.identification.identify(item) {
+            game.messages
+                .add(format!("That was a {}!", name), LIGHT_CYAN);
+        }
+        match result {
             UseResult::UsedUp => {
-                game.inventory.remove(inventory_id);
+                split_stack(objects.player_mut().inventory_mut(), inventory_id, 1);
             }
             UseResult::Cancelled => {
                 game.messages.add("Cancelled", WHITE);
@@ -175,7 +549,10 @@ pub fn use_item(inventory_id: usize, tcod: &mut Tcod, game: &mut Game, objects:
         }
     } else {
         game.messages.add(
-            format!("The {} cannot be used.", game.inventory[inventory_id].name),
+            format!(
+                "The {} cannot be used.",
+                objects.player().inventory()[inventory_id].name
+            ),
             WHITE,
         );
     }
@@ -184,9 +561,8 @@ pub fn use_item(inventory_id: usize, tcod: &mut Tcod, game: &mut Game, objects:
 pub fn get_equipped_in_slot(slot: Slot, inventory: &[Object]) -> Option<usize> {
     for (inventory_id, item) in inventory.iter().enumerate() {
         if item
-            .equipment
-            .as_ref()
-            .map_or(false, |e| e.equipped && e.slot == slot)
+            .get::<Equipment>()
+            .is_some_and(|e| e.equipped && e.fills(slot))
         {
             return Some(inventory_id);
         }
@@ -194,23 +570,53 @@ pub fn get_equipped_in_slot(slot: Slot, inventory: &[Object]) -> Option<usize> {
     None
 }
 
+pub fn free_slot(equipment: &Equipment, inventory: &[Object]) -> Option<Slot> {
+    let is_free = |slot| get_equipped_in_slot(slot, inventory).is_none();
+    if equipment.grip == Grip::TwoHanded {
+        return (is_free(Slot::RightHand) && is_free(Slot::LeftHand)).then_some(Slot::RightHand);
+    }
+    equipment.slots().into_iter().find(|&slot| is_free(slot))
+}
+
+// whatever has to come off before the item can go in its own slot
+fn blocking(equipment: &Equipment, inventory: &[Object]) -> Vec<usize> {
+    let mut blocking: Vec<usize> = Slot::ALL
+        .into_iter()
+        .filter(|&slot| equipment.fills(slot))
+        .filter_map(|slot| get_equipped_in_slot(slot, inventory))
+        .collect();
+    blocking.dedup();
+    blocking
+}
+
 fn toggle_equipment(
     inventory_id: usize,
     _tcod: &mut Tcod,
     game: &mut Game,
-    _objects: &mut [Object],
+    objects: &mut Objects,
 ) -> UseResult {
-    let equipment = match game.inventory[inventory_id].equipment {
-        Some(equipment) => equipment,
+    let inventory = objects.player_mut().inventory_mut();
+    let equipment = match inventory[inventory_id].get::<Equipment>() {
+        Some(&equipment) => equipment,
         None => return UseResult::Cancelled,
     };
     if equipment.equipped {
-        game.inventory[inventory_id].dequip(&mut game.messages);
+        inventory[inventory_id].dequip(&mut game.messages);
     } else {
-        if let Some(current) = get_equipped_in_slot(equipment.slot, &game.inventory) {
-            game.inventory[current].dequip(&mut game.messages);
-        }
-        game.inventory[inventory_id].equip(&mut game.messages);
+        let slot = match free_slot(&equipment, inventory) {
+            Some(slot) => slot,
+            None => {
+                let slot = equipment.slots()[0];
+                let equipment = Equipment { slot, ..equipment };
+                for current in blocking(&equipment, inventory) {
+                    if !inventory[current].dequip(&mut game.messages) {
+                        return UseResult::UsedAndKept;
+                    }
+                }
+                slot
+            }
+        };
+        inventory[inventory_id].equip_in(slot, &mut game.messages);
     }
     UseResult::UsedAndKept
 }
@@ -219,16 +625,14 @@ fn cast_heal(
     _inventory_id: usize,
     _tcod: &mut Tcod,
     game: &mut Game,
-    objects: &mut [Object],
+    objects: &mut Objects,
 ) -> UseResult {
-    if let Some(fighter) = objects[PLAYER].fighter {
-        if fighter.hp == objects[PLAYER].max_hp(game) {
+    if let Some(&fighter) = objects.player().get::<Fighter>() {
+        if fighter.hp == objects.player().max_hp() {
             game.messages.add("You are already at full health.", RED);
             return UseResult::Cancelled;
         }
-        game.messages
-            .add("Your wounds start to feel better!", LIGHT_VIOLET);
-        objects[PLAYER].heal(HEAL_AMOUNT, game);
+        spell::heal(objects.player_id(), HEAL_AMOUNT, game, objects);
         return UseResult::UsedUp;
     }
     UseResult::Cancelled
@@ -238,21 +642,11 @@ fn cast_lightning(
     _inventory_id: usize,
     tcod: &mut Tcod,
     game: &mut Game,
-    objects: &mut [Object],
+    objects: &mut Objects,
 ) -> UseResult {
     let monster_id = closest_monster(tcod, objects, LIGHTNING_RANGE);
     if let Some(monster_id) = monster_id {
-        game.messages.add(
-            format!(
-                "A lightning bolt strikes the {} with a loud thunder! \
-                 The damage is {} hit points.",
-                objects[monster_id].name, LIGHTNING_DAMAGE
-            ),
-            LIGHT_BLUE,
-        );
-        if let Some(xp) = objects[monster_id].take_damage(LIGHTNING_DAMAGE, game) {
-            objects[PLAYER].fighter.as_mut().unwrap().xp += xp;
-        };
+        spell::lightning(objects.player_id(), monster_id, game, objects);
         UseResult::UsedUp
     } else {
         game.messages
@@ -265,22 +659,11 @@ fn cast_confuse(
     _inventory_id: usize,
     tcod: &mut Tcod,
     game: &mut Game,
-    objects: &mut [Object],
+    objects: &mut Objects,
 ) -> UseResult {
     let monster_id = target_monster(tcod, game, objects, Some(CONFUSE_RANGE as f32));
     if let Some(monster_id) = monster_id {
-        let old_ai = objects[monster_id].ai.take().unwrap_or(Ai::Basic);
-        objects[monster_id].ai = Some(Ai::Confused {
-            previous_ai: Box::new(old_ai),
-            num_turns: CONFUSE_NUM_TURNS,
-        });
-        game.messages.add(
-            format!(
-                "The eyes of {} look vacant, as he starts to stumble around!",
-                objects[monster_id].name
-            ),
-            LIGHT_GREEN,
-        );
+        spell::confuse(monster_id, game, objects);
         UseResult::UsedUp
     } else {
         game.messages.add("No enemy is close enough to strike", RED);
@@ -292,7 +675,7 @@ fn cast_fireball(
     _inventory_id: usize,
     tcod: &mut Tcod,
     game: &mut Game,
-    objects: &mut [Object],
+    objects: &mut Objects,
 ) -> UseResult {
     game.messages.add(
         "Left-click a target tile for the fireball, or right-click to cancel.",
@@ -304,34 +687,7 @@ fn cast_fireball(
         None => return UseResult::Cancelled,
     };
 
-    game.messages.add(
-        format!(
-            "The fireball explodes, burning everything within {} tiles!",
-            FIREBALL_RADIUS
-        ),
-        ORANGE,
-    );
-
-    let mut xp_to_gain = 0;
-    for (id, obj) in objects.iter_mut().enumerate() {
-        if obj.distance(x, y) <= FIREBALL_RADIUS as f32 && obj.fighter.is_some() {
-            game.messages.add(
-                format!(
-                    "The {} gets burned for {} hit points.",
-                    obj.name, FIREBALL_DAMAGE
-                ),
-                ORANGE,
-            );
-            if let Some(xp) = obj.take_damage(FIREBALL_DAMAGE, game) {
-                if id != PLAYER {
-                    xp_to_gain += xp;
-                }
-            }
-        }
-    }
-
-    objects[PLAYER].fighter.as_mut().unwrap().xp += xp_to_gain;
-
+    spell::fireball(objects.player_id(), x, y, game, objects);
     UseResult::UsedUp
 }
 
@@ -339,7 +695,7 @@ fn cast_blink(
     _inventory_id: usize,
     tcod: &mut Tcod,
     game: &mut Game,
-    objects: &mut [Object],
+    objects: &mut Objects,
 ) -> UseResult {
     game.messages.add(
         "Left-click a target tile to blink to that location.",
@@ -359,11 +715,7 @@ fn cast_blink(
         return UseResult::Cancelled;
     }
 
-    game.messages
-        .add("You teleport to the new location!", LIGHT_GREEN);
-
-    objects[PLAYER].set_pos(x, y);
-
+    spell::blink(objects.player_id(), x, y, game, objects);
     UseResult::UsedUp
 }
 
@@ -371,22 +723,11 @@ fn cast_freeze(
     _inventory_id: usize,
     tcod: &mut Tcod,
     game: &mut Game,
-    objects: &mut [Object],
+    objects: &mut Objects,
 ) -> UseResult {
     let monster_id = target_monster(tcod, game, objects, Some(FREEZE_RANGE as f32));
     if let Some(monster_id) = monster_id {
-        let old_ai = objects[monster_id].ai.take().unwrap_or(Ai::Basic);
-        objects[monster_id].ai = Some(Ai::Frozen {
-            previous_ai: Box::new(old_ai),
-            num_turns: FREEZE_NUM_TURNS,
-        });
-        game.messages.add(
-            format!(
-                "The {} is frozen solid and cannot move!",
-                objects[monster_id].name
-            ),
-            LIGHT_BLUE,
-        );
+        spell::freeze(monster_id, game, objects);
         UseResult::UsedUp
     } else {
         game.messages
@@ -395,17 +736,295 @@ fn cast_freeze(
     }
 }
 
-fn closest_monster(tcod: &Tcod, objects: &[Object], max_range: i32) -> Option<usize> {
+fn cast_identify(
+    inventory_id: usize,
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+) -> UseResult {
+    let unknown: Vec<usize> = objects
+        .player()
+        .inventory()
+        .iter()
+        .enumerate()
+        .filter(|&(index, item)| index != inventory_id && game.identification.is_unknown(item))
+        .map(|(index, _)| index)
+        .collect();
+    if unknown.is_empty() {
+        game.messages.add("You have nothing to identify.", WHITE);
+        // an unknown scroll is still learned about by reading it
+        return if game.identification.is_identified(Item::Identify) {
+            UseResult::Cancelled
+        } else {
+            UseResult::UsedUp
+        };
+    }
+
+    let inventory = objects.player().inventory();
+    let options: Vec<_> = unknown
+        .iter()
+        .map(|&index| game.identification.display_name(&inventory[index]))
+        .collect();
+    let Some(choice) = menu(
+        "Choose an item to identify:\n",
+        &options,
+        INVENTORY_WIDTH,
+        &mut tcod.root,
+    ) else {
+        return UseResult::Cancelled;
+    };
+    let item = &mut objects.player_mut().inventory_mut()[unknown[choice]];
+    let appearance = game.identification.name(item);
+    if let Some(&kind) = item.get::<Item>()
+        && game.identification.identify(kind)
+    {
+        game.messages.add(
+            format!("The {} is a {}.", appearance, item.name),
+            LIGHT_CYAN,
+        );
+    }
+    if let Some(equipment) = item.get_mut::<Equipment>()
+        && !equipment.curse_known
+    {
+        equipment.curse_known = true;
+        let verdict = if equipment.cursed {
+            "is cursed!"
+        } else {
+            "is free of curses."
+        };
+        game.messages
+            .add(format!("The {} {}", item.name, verdict), LIGHT_CYAN);
+    }
+    UseResult::UsedUp
+}
+
+fn cast_remove_curse(
+    _inventory_id: usize,
+    _tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+) -> UseResult {
+    let mut lifted = false;
+    for item in objects.player_mut().inventory_mut() {
+        if let Some(equipment) = item.get_mut::<Equipment>()
+            && equipment.equipped
+            && equipment.cursed
+        {
+            equipment.cursed = false;
+            equipment.curse_known = true;
+            lifted = true;
+        }
+    }
+    if lifted {
+        game.messages
+            .add("You feel as if someone is watching over you.", LIGHT_CYAN);
+    } else {
+        game.messages.add("You feel a faint tingle.", WHITE);
+    }
+    UseResult::UsedUp
+}
+
+fn cast_wand(
+    inventory_id: usize,
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+) -> UseResult {
+    let wand = &objects.player().inventory()[inventory_id];
+    let Some(&Item::Wand(spell)) = wand.get::<Item>() else {
+        return UseResult::Cancelled;
+    };
+    if wand.get::<Charges>().map_or(0, |charges| charges.current) <= 0 {
+        game.messages.add(
+            format!("The {} is out of charges.", game.identification.name(wand)),
+            WHITE,
+        );
+        return UseResult::Cancelled;
+    }
+
+    let on_zap = match spell {
+        Spell::Heal => cast_heal,
+        Spell::Lightning => cast_lightning,
+        Spell::Confuse => cast_confuse,
+        Spell::Fireball => cast_fireball,
+        Spell::Blink => cast_blink,
+        Spell::Freeze => cast_freeze,
+        Spell::Charm => cast_charm,
+    };
+    match on_zap(inventory_id, tcod, game, objects) {
+        UseResult::Cancelled => UseResult::Cancelled,
+        _ => {
+            if let Some(charges) =
+                objects.player_mut().inventory_mut()[inventory_id].get_mut::<Charges>()
+            {
+                charges.current -= 1;
+            }
+            UseResult::UsedAndKept
+        }
+    }
+}
+
+fn cast_recharge(
+    inventory_id: usize,
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+) -> UseResult {
+    let wands: Vec<usize> = objects
+        .player()
+        .inventory()
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.has::<Charges>())
+        .map(|(index, _)| index)
+        .collect();
+    if wands.is_empty() {
+        game.messages.add("You have nothing to recharge.", WHITE);
+        return if game.identification.is_identified(Item::Recharge) {
+            UseResult::Cancelled
+        } else {
+            UseResult::UsedUp
+        };
+    }
+
+    let inventory = objects.player().inventory();
+    let options: Vec<_> = wands
+        .iter()
+        .map(|&index| game.identification.display_name(&inventory[index]))
+        .collect();
+    let Some(choice) = menu(
+        "Choose a wand to recharge:\n",
+        &options,
+        INVENTORY_WIDTH,
+        &mut tcod.root,
+    ) else {
+        return UseResult::Cancelled;
+    };
+    let wand_id = wands[choice];
+    let wand = &mut objects.player_mut().inventory_mut()[wand_id];
+    let Some(charges) = wand.get_mut::<Charges>() else {
+        return UseResult::Cancelled;
+    };
+    if charges.recharged && rand::rng().random_range(0..100) < RECHARGE_BREAK_CHANCE {
+        // the scroll goes first so that losing the wand cannot shift it out from under use_item
+        let wand_id = use_up_scroll(objects.player_mut().inventory_mut(), inventory_id, wand_id);
+        explode_wand(wand_id, game, objects);
+        return UseResult::UsedAndKept;
+    }
+    charges.current = charges.max;
+    charges.recharged = true;
+    let name = game.identification.name(wand);
+    game.messages
+        .add(format!("Your {} glows brightly.", name), LIGHT_CYAN);
+    UseResult::UsedUp
+}
+
+pub fn break_wand(tcod: &mut Tcod, game: &mut Game, objects: &mut Objects) -> bool {
+    let wands: Vec<usize> = objects
+        .player()
+        .inventory()
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.has::<Charges>())
+        .map(|(index, _)| index)
+        .collect();
+    if wands.is_empty() {
+        game.messages.add("You have no wands to break.", WHITE);
+        return false;
+    }
+
+    let inventory = objects.player().inventory();
+    let options: Vec<_> = wands
+        .iter()
+        .map(|&index| game.identification.display_name(&inventory[index]))
+        .collect();
+    match menu(
+        "Choose a wand to break:\n",
+        &options,
+        INVENTORY_WIDTH,
+        &mut tcod.root,
+    ) {
+        Some(choice) => {
+            explode_wand(wands[choice], game, objects);
+            true
+        }
+        None => false,
+    }
+}
+
+fn use_up_scroll(inventory: &mut Vec<Object>, scroll_id: usize, wand_id: usize) -> usize {
+    let before = inventory.len();
+    split_stack(inventory, scroll_id, 1);
+    if inventory.len() < before && wand_id > scroll_id {
+        wand_id - 1
+    } else {
+        wand_id
+    }
+}
+
+fn explode_wand(inventory_id: usize, game: &mut Game, objects: &mut Objects) {
+    let wand = objects.player_mut().inventory_mut().remove(inventory_id);
+    let name = game.identification.name(&wand);
+    let charges = wand.get::<Charges>().map_or(0, |charges| charges.current);
+    match wand.get::<Item>() {
+        Some(&Item::Wand(spell)) if charges > 0 => {
+            game.messages
+                .add(format!("Your {} explodes!", name), ORANGE);
+            game.identification.identify(Item::Wand(spell));
+            spell::burst(objects.player_id(), spell, charges, game, objects);
+        }
+        _ => game
+            .messages
+            .add(format!("Your {} crumbles to dust.", name), WHITE),
+    }
+}
+
+fn cast_charm(
+    _inventory_id: usize,
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+) -> UseResult {
+    game.messages.add(
+        "Left-click an enemy to charm it, or right-click to cancel.",
+        LIGHT_CYAN,
+    );
+    let monster_id = target_monster(tcod, game, objects, Some(CHARM_RANGE as f32));
+    match monster_id {
+        Some(monster_id) if objects[monster_id].faction == Faction::Player => {
+            game.messages.add(
+                format!("The {} is already on your side.", objects[monster_id].name),
+                RED,
+            );
+            UseResult::Cancelled
+        }
+        Some(monster_id) if !objects[monster_id].is_charmable() => {
+            game.messages.add(
+                format!("The {} resists your charm!", objects[monster_id].name),
+                RED,
+            );
+            UseResult::UsedUp
+        }
+        Some(monster_id) => {
+            spell::charm(objects.player_id(), monster_id, game, objects);
+            UseResult::UsedUp
+        }
+        None => UseResult::Cancelled,
+    }
+}
+
+fn closest_monster(tcod: &Tcod, objects: &Objects, max_range: i32) -> Option<EntityId> {
     let mut closest_enemy = None;
     let mut closest_dist = (max_range + 1) as f32;
 
-    for (id, object) in objects.iter().enumerate() {
-        if (id != PLAYER)
-            && object.fighter.is_some()
-            && object.ai.is_some()
+    for (id, object) in objects.entries() {
+        if (id != objects.player_id())
+            && object.has::<Fighter>()
+            && object.has::<Ai>()
+            && Faction::Player.is_hostile_to(object.faction)
             && tcod.fov.is_in_fov(object.x, object.y)
         {
-            let dist = objects[PLAYER].distance_to(object);
+            let dist = objects.player().distance_to(object);
             if dist < closest_dist {
                 closest_enemy = Some(id);
                 closest_dist = dist;
@@ -418,7 +1037,7 @@ fn closest_monster(tcod: &Tcod, objects: &[Object], max_range: i32) -> Option<us
 fn target_tile(
     tcod: &mut Tcod,
     game: &mut Game,
-    objects: &[Object],
+    objects: &Objects,
     max_range: Option<f32>,
 ) -> Option<(i32, i32)> {
     use tcod::input::KeyCode::Escape;
@@ -435,7 +1054,7 @@ fn target_tile(
         let (x, y) = (tcod.mouse.cx as i32, tcod.mouse.cy as i32);
 
         let in_fov = (x < MAP_WIDTH) && (y < MAP_HEIGHT) && tcod.fov.is_in_fov(x, y);
-        let in_range = max_range.map_or(true, |range| objects[PLAYER].distance(x, y) <= range);
+        let in_range = max_range.is_none_or(|range| objects.player().distance(x, y) <= range);
         if tcod.mouse.lbutton_pressed && in_fov && in_range {
             return Some((x, y));
         }
@@ -446,17 +1065,17 @@ fn target_tile(
     }
 }
 
-fn target_monster(
+pub fn target_monster(
     tcod: &mut Tcod,
     game: &mut Game,
-    objects: &[Object],
+    objects: &Objects,
     max_range: Option<f32>,
-) -> Option<usize> {
+) -> Option<EntityId> {
     loop {
         match target_tile(tcod, game, objects, max_range) {
             Some((x, y)) => {
-                for (id, obj) in objects.iter().enumerate() {
-                    if obj.pos() == (x, y) && obj.fighter.is_some() && id != PLAYER {
+                for (id, obj) in objects.entries() {
+                    if obj.pos() == (x, y) && obj.has::<Fighter>() && id != objects.player_id() {
                         return Some(id);
                     }
                 }
@@ -465,3 +1084,208 @@ fn target_monster(
         }
     }
 }
+
+// lets tests elsewhere build items from the real templates
+#[cfg(test)]
+pub(crate) fn load_test_templates() {
+    ITEMS.get_or_init(|| parse_items(include_str!("../items.json")).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Messages;
+    use crate::identify::Identification;
+
+    const ITEMS_JSON: &str = include_str!("../items.json");
+
+    fn templates() -> &'static [ItemTemplate] {
+        load_test_templates();
+        item_templates()
+    }
+
+    #[test]
+    fn every_item_has_exactly_one_template() {
+        for item in Item::ALL {
+            let count = templates()
+                .iter()
+                .filter(|template| template.item == item)
+                .count();
+            assert_eq!(count, 1, "{:?}", item);
+        }
+    }
+
+    #[test]
+    fn created_items_match_their_templates() {
+        for template in templates() {
+            let object = create_item(template.item, 3, 4);
+            let [r, g, b] = template.color;
+            assert_eq!(object.name, template.name);
+            assert_eq!(object.char, template.glyph);
+            assert_eq!(object.color, Color::new(r, g, b));
+            assert_eq!(object.pos(), (3, 4));
+            assert_eq!(object.get::<Item>(), Some(&template.item));
+            assert_eq!(object.get::<Equipment>(), template.equipment.as_ref());
+            assert!(!object.blocks);
+        }
+    }
+
+    #[test]
+    fn equipment_templates_are_sane() {
+        for template in templates() {
+            if let Some(equipment) = template.equipment {
+                assert!(template.item.is_equipment());
+                assert!(!equipment.equipped, "{}", template.name);
+                let mut bonus =
+                    equipment.max_hp_bonus + equipment.defense_bonus + equipment.power_bonus;
+                // ranged gear does its damage when fired rather than through stats
+                bonus += template.launcher.map_or(0, |launcher| launcher.power);
+                if equipment.slot == Slot::Quiver {
+                    bonus += template.weight;
+                }
+                assert!(
+                    bonus > 0 && bonus < 100,
+                    "{} has bonus {}",
+                    template.name,
+                    bonus
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn identical_consumables_stack_up_to_the_limit() {
+        templates();
+        let mut inventory = vec![];
+        for _ in 0..MAX_STACK_SIZE + 2 {
+            add_to_inventory(&mut inventory, create_item(Item::Heal, 0, 0));
+        }
+        add_to_inventory(&mut inventory, create_item(Item::Fireball, 0, 0));
+        add_to_inventory(&mut inventory, create_item(Item::Sword, 0, 0));
+        add_to_inventory(&mut inventory, create_item(Item::Sword, 0, 0));
+
+        let counts: Vec<_> = inventory.iter().map(|item| item.count()).collect();
+        assert_eq!(counts, vec![MAX_STACK_SIZE, 2, 1, 1, 1]);
+        let identification = Identification::default();
+        assert_eq!(
+            identification.display_name(&inventory[1]),
+            "2 healing potions"
+        );
+    }
+
+    #[test]
+    fn splitting_a_stack_leaves_the_rest() {
+        templates();
+        let mut inventory = vec![];
+        for _ in 0..3 {
+            add_to_inventory(&mut inventory, create_item(Item::Confuse, 0, 0));
+        }
+        let dropped = split_stack(&mut inventory, 0, 2);
+        assert_eq!(
+            Identification::default().display_name(&dropped),
+            "2 scrolls of confusion"
+        );
+        assert_eq!(inventory[0].count(), 1);
+
+        split_stack(&mut inventory, 0, 1);
+        assert!(inventory.is_empty());
+    }
+
+    #[test]
+    fn a_breaking_wand_keeps_track_of_the_scroll() {
+        templates();
+        let wand = create_item(Item::Wand(Spell::Lightning), 0, 0);
+        let scroll = create_item(Item::Recharge, 0, 0);
+
+        let mut inventory = vec![wand.clone(), scroll.clone()];
+        let wand_id = use_up_scroll(&mut inventory, 1, 0);
+        assert_eq!(inventory.len(), 1);
+        assert_eq!(
+            inventory[wand_id].get::<Item>(),
+            Some(&Item::Wand(Spell::Lightning))
+        );
+
+        let mut inventory = vec![scroll.clone(), wand.clone()];
+        let wand_id = use_up_scroll(&mut inventory, 0, 1);
+        assert_eq!(wand_id, 0);
+
+        let mut inventory = vec![scroll, wand];
+        inventory[0].set_count(2);
+        let wand_id = use_up_scroll(&mut inventory, 0, 1);
+        assert_eq!(wand_id, 1);
+        assert_eq!(inventory[0].count(), 1);
+    }
+
+    #[test]
+    fn a_second_ring_goes_on_the_other_hand() {
+        templates();
+        let mut messages = Messages::new();
+        let mut inventory = vec![];
+        for _ in 0..2 {
+            let mut ring = create_item(Item::ProtectionRing, 0, 0);
+            let equipment = *ring.get::<Equipment>().unwrap();
+            let slot = free_slot(&equipment, &inventory).unwrap();
+            ring.equip_in(slot, &mut messages);
+            inventory.push(ring);
+        }
+        assert!(get_equipped_in_slot(Slot::LeftRing, &inventory).is_some());
+        assert!(get_equipped_in_slot(Slot::RightRing, &inventory).is_some());
+        let equipment = item_template(Item::ProtectionRing).equipment.unwrap();
+        assert_eq!(free_slot(&equipment, &inventory), None);
+    }
+
+    #[test]
+    fn two_handed_weapons_fill_both_hands() {
+        templates();
+        let mut greatsword = create_item(Item::Greatsword, 0, 0);
+        greatsword.equip_in(Slot::RightHand, &mut Messages::new());
+        let inventory = vec![greatsword];
+        assert_eq!(get_equipped_in_slot(Slot::LeftHand, &inventory), Some(0));
+
+        let shield = item_template(Item::Shield).equipment.unwrap();
+        assert_eq!(free_slot(&shield, &inventory), None);
+        assert_eq!(blocking(&shield, &inventory), vec![0]);
+    }
+
+    #[test]
+    fn the_off_hand_weapon_hits_softer() {
+        templates();
+        let mut messages = Messages::new();
+        let mut fighter = Object::new(0, 0, '@', WHITE, "fighter", true);
+        for _ in 0..2 {
+            let mut sword = create_item(Item::Sword, 0, 0);
+            let equipment = *sword.get::<Equipment>().unwrap();
+            let slot = free_slot(&equipment, fighter.inventory()).unwrap();
+            sword.equip_in(slot, &mut messages);
+            fighter.inventory_mut().push(sword);
+        }
+        let power = item_template(Item::Sword).equipment.unwrap().power_bonus;
+        assert_eq!(
+            fighter.power_bonus(),
+            power + power * OFF_HAND_POWER_PERCENT / 100
+        );
+    }
+
+    #[test]
+    fn duplicate_templates_are_rejected() {
+        let mut json = ITEMS_JSON.trim_end().trim_end_matches(']').to_string();
+        json.push_str(
+            r#", {"item": "Heal", "name": "other potion", "glyph": "!", "color": [0, 0, 0]}]"#,
+        );
+        let error = parse_items(&json).unwrap_err().to_string();
+        assert!(error.contains("defined twice"), "{}", error);
+    }
+
+    #[test]
+    fn weapon_without_equipment_is_rejected() {
+        let mut items: Vec<serde_json::Value> = serde_json::from_str(ITEMS_JSON).unwrap();
+        let sword = items
+            .iter_mut()
+            .find(|item| item["item"] == "Sword")
+            .unwrap();
+        sword.as_object_mut().unwrap().remove("equipment").unwrap();
+        let json = serde_json::to_string(&items).unwrap();
+        let error = parse_items(&json).unwrap_err().to_string();
+        assert!(error.contains("missing its equipment"), "{}", error);
+    }
+}
@@ -1,10 +1,19 @@
+pub mod affix;
 pub mod ai;
+pub mod boss;
+pub mod component;
 pub mod constants;
+pub mod entity;
 pub mod game;
+pub mod identify;
 pub mod item;
 pub mod map;
 pub mod meta;
+pub mod monster;
 pub mod object;
+pub mod projectile;
+pub mod spell;
+pub mod status;
 pub mod ui;
 
 pub fn mut_two<T>(first_index: usize, second_index: usize, items: &mut [T]) -> (&mut T, &mut T) {
@@ -1,10 +1,26 @@
+use roguelike::affix::load_affixes;
 use roguelike::constants::*;
 use roguelike::game::Tcod;
+use roguelike::item::load_items;
+use roguelike::monster::load_monsters;
 use roguelike::ui::main_menu;
 use tcod::console::*;
 use tcod::map::Map as FovMap;
 
 fn main() {
+    if let Err(e) = load_monsters(MONSTERS_FILE) {
+        eprintln!("Could not load monsters from {}: {}", MONSTERS_FILE, e);
+        std::process::exit(1);
+    }
+    if let Err(e) = load_items(ITEMS_FILE) {
+        eprintln!("Could not load items from {}: {}", ITEMS_FILE, e);
+        std::process::exit(1);
+    }
+    if let Err(e) = load_affixes(AFFIXES_FILE) {
+        eprintln!("Could not load affixes from {}: {}", AFFIXES_FILE, e);
+        std::process::exit(1);
+    }
+
     tcod::system::set_fps(LIMIT_FPS);
 
     let root = Root::initializer()
@@ -21,6 +37,7 @@ fn main() {
         fov: FovMap::new(MAP_WIDTH, MAP_HEIGHT),
         key: Default::default(),
         mouse: Default::default(),
+        projectile: None,
     };
 
     main_menu(&mut tcod);
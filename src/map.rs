@@ -1,4 +1,9 @@
+use crate::affix::generate_item;
+use crate::boss::{Boss, create_boss};
 use crate::constants::*;
+use crate::entity::Objects;
+use crate::item::item_templates;
+use crate::monster::{Variant, create_monster, templates};
 use crate::object::*;
 use rand::Rng;
 use rand::distr::Distribution;
@@ -6,6 +11,7 @@ use rand::distr::weighted::WeightedIndex;
 use serde::{Deserialize, Serialize};
 use std::cmp;
 use tcod::colors::*;
+use tcod::line::Line;
 
 pub type Map = Vec<Vec<Tile>>;
 
@@ -66,7 +72,7 @@ impl Rect {
     }
 }
 
-pub fn is_blocked(x: i32, y: i32, map: &Map, objects: &[Object]) -> bool {
+pub fn is_blocked(x: i32, y: i32, map: &Map, objects: &Objects) -> bool {
     if map[x as usize][y as usize].blocked {
         return true;
     }
@@ -76,10 +82,64 @@ pub fn is_blocked(x: i32, y: i32, map: &Map, objects: &[Object]) -> bool {
         .any(|object| object.blocks && object.pos() == (x, y))
 }
 
-pub fn make_map(objects: &mut Vec<Object>) -> Map {
-    let mut map = vec![vec![Tile::wall(); MAP_HEIGHT as usize]; MAP_WIDTH as usize];
+pub fn free_tile_near(x: i32, y: i32, map: &Map, objects: &Objects) -> Option<(i32, i32)> {
+    for radius in 1..3 {
+        for dx in -radius..=radius {
+            for dy in -radius..=radius {
+                let (near_x, near_y) = (x + dx, y + dy);
+                if near_x > 0
+                    && near_x < MAP_WIDTH - 1
+                    && near_y > 0
+                    && near_y < MAP_HEIGHT - 1
+                    && !is_blocked(near_x, near_y, map, objects)
+                {
+                    return Some((near_x, near_y));
+                }
+            }
+        }
+    }
+    None
+}
 
-    objects.truncate(1);
+pub fn is_corridor(x: i32, y: i32, map: &Map) -> bool {
+    let blocked = |x: i32, y: i32| map[x as usize][y as usize].blocked;
+    (blocked(x - 1, y) && blocked(x + 1, y)) || (blocked(x, y - 1) && blocked(x, y + 1))
+}
+
+pub fn has_line_of_sight(from: (i32, i32), to: (i32, i32), map: &Map) -> bool {
+    Line::new(from, to)
+        .take_while(|&pos| pos != to)
+        .all(|(x, y)| !map[x as usize][y as usize].block_sight)
+}
+
+// depth tables list (depth, value) points; depths between points interpolate,
+// depths before the first point get 0 and depths past the last keep its value
+pub fn from_dungeon_level(table: &[(u32, u32)], level: u32) -> u32 {
+    let Some(index) = table.iter().rposition(|&(depth, _)| depth <= level) else {
+        return 0;
+    };
+    let (depth, value) = table[index];
+    match table.get(index + 1) {
+        Some(&(next_depth, next_value)) => {
+            let progress = (level - depth) as f32 / (next_depth - depth) as f32;
+            (value as f32 + (next_value as f32 - value as f32) * progress).round() as u32
+        }
+        None => value,
+    }
+}
+
+pub fn check_depth_table(table: &[(u32, u32)]) -> Result<(), String> {
+    if table.iter().any(|&(depth, _)| depth == 0) {
+        return Err("depths start at 1".into());
+    }
+    if table.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+        return Err("depths must be strictly increasing".into());
+    }
+    Ok(())
+}
+
+pub fn make_map(objects: &mut Objects, level: u32) -> Map {
+    let mut map = vec![vec![Tile::wall(); MAP_HEIGHT as usize]; MAP_WIDTH as usize];
 
     let mut rooms = vec![];
 
@@ -98,12 +158,12 @@ pub fn make_map(objects: &mut Vec<Object>) -> Map {
 
         if !failed {
             create_room(new_room, &mut map);
-            place_objects(new_room, &map, objects);
+            place_objects(new_room, &map, objects, level);
 
             let (new_x, new_y) = new_room.center();
 
             if rooms.is_empty() {
-                objects[PLAYER].set_pos(new_x, new_y);
+                objects.player_mut().set_pos(new_x, new_y);
             } else {
                 let (prev_x, prev_y) = rooms[rooms.len() - 1].center();
 
@@ -123,7 +183,13 @@ pub fn make_map(objects: &mut Vec<Object>) -> Map {
     let (last_room_x, last_room_y) = rooms[rooms.len() - 1].center();
     let mut stairs = Object::new(last_room_x, last_room_y, '<', WHITE, "stairs", false);
     stairs.always_visible = true;
-    objects.push(stairs);
+    objects.insert(stairs);
+
+    if let Some(boss) = Boss::for_level(level) {
+        let (x, y) = free_tile_near(last_room_x, last_room_y, &map, objects)
+            .unwrap_or((last_room_x, last_room_y));
+        objects.insert(create_boss(boss, x, y));
+    }
 
     map
 }
@@ -148,134 +214,76 @@ fn create_v_tunnel(y1: i32, y2: i32, x: i32, map: &mut Map) {
     }
 }
 
-fn place_objects(room: Rect, map: &Map, objects: &mut Vec<Object>) {
+fn spawn_pack(leader: Object, member: &str, level: u32, map: &Map, objects: &mut Objects) {
+    let (x, y) = leader.pos();
+    let leader = objects.insert(leader);
+    objects[leader].insert(Ai::Pack { leader });
+
+    let num_members = rand::rng().random_range(PACK_MIN_MEMBERS..=PACK_MAX_MEMBERS);
+    for _ in 0..num_members {
+        if let Some((member_x, member_y)) = free_tile_near(x, y, map, objects) {
+            let mut monster = create_monster(member, member_x, member_y, level);
+            monster.insert(Ai::Pack { leader });
+            objects.insert(monster);
+        }
+    }
+}
+
+fn place_objects(room: Rect, map: &Map, objects: &mut Objects, level: u32) {
     let mut rng = rand::rng();
-    let num_monsters = rng.random_range(0..(MAX_ROOM_MONSTERS + 1));
-    let num_items = rng.random_range(0..(MAX_ROOM_ITEMS + 1));
-
-    let monster_choices = ["orc", "troll"];
-    let monster_weights = [4, 1];
-    let monster_dist = WeightedIndex::new(&monster_weights).unwrap();
-
-    let item_choices = [
-        Item::Heal,
-        Item::Lightning,
-        Item::Fireball,
-        Item::Confuse,
-        Item::Blink,
-        Item::Freeze,
-        Item::Sword,
-        Item::Shield,
-    ];
-    let item_weights = [4, 3, 3, 3, 3, 3, 2, 2];
-    let item_dist = WeightedIndex::new(&item_weights).unwrap();
+    let num_monsters = rng.random_range(0..=from_dungeon_level(MAX_ROOM_MONSTERS, level));
+    let num_items = rng.random_range(0..=from_dungeon_level(MAX_ROOM_ITEMS, level));
+
+    let monster_choices: Vec<_> = templates()
+        .iter()
+        .filter(|template| template.spawns_at(level))
+        .collect();
+    let monster_dist = WeightedIndex::new(
+        monster_choices
+            .iter()
+            .map(|template| template.spawn_weight_at(level)),
+    );
+
+    let item_choices = item_templates();
+    let item_dist = WeightedIndex::new(
+        item_choices
+            .iter()
+            .map(|template| template.spawn_weight_at(level)),
+    );
 
     for _ in 0..num_monsters {
+        // a depth without any spawnable monster leaves its rooms empty
+        let Ok(monster_dist) = &monster_dist else {
+            break;
+        };
         let x = rng.random_range((room.x1 + 1)..room.x2);
         let y = rng.random_range((room.y1 + 1)..room.y2);
 
         if !is_blocked(x, y, map, objects) {
-            let mut monster = match monster_choices[monster_dist.sample(&mut rng)] {
-                "orc" => {
-                    let mut orc = Object::new(x, y, 'o', DESATURATED_GREEN, "orc", true);
-                    orc.fighter = Some(Fighter {
-                        base_max_hp: 20,
-                        hp: 20,
-                        base_defense: 0,
-                        base_power: 4,
-                        xp: 35,
-                        on_death: DeathCallback::Monster,
-                    });
-                    orc.ai = Some(Ai::Basic);
-                    orc
-                }
-                "troll" => {
-                    let mut troll = Object::new(x, y, 'T', DARKER_GREEN, "troll", true);
-                    troll.fighter = Some(Fighter {
-                        base_max_hp: 30,
-                        hp: 30,
-                        base_defense: 2,
-                        base_power: 8,
-                        xp: 100,
-                        on_death: DeathCallback::Monster,
-                    });
-                    troll.ai = Some(Ai::Basic);
-                    troll
+            let template = monster_choices[monster_dist.sample(&mut rng)];
+            let mut monster = create_monster(&template.name, x, y, level);
+            if let Some(variant) = Variant::roll(level) {
+                variant.apply(&mut monster);
+            }
+            match &template.pack {
+                Some(member) => spawn_pack(monster, member, level, map, objects),
+                None => {
+                    objects.insert(monster);
                 }
-                _ => unreachable!(),
-            };
-            monster.alive = true;
-            objects.push(monster);
+            }
         }
     }
 
     for _ in 0..num_items {
+        let Ok(item_dist) = &item_dist else {
+            break;
+        };
         let x = rand::rng().random_range((room.x1 + 1)..room.x2);
         let y = rand::rng().random_range((room.y1 + 1)..room.y2);
 
         if !is_blocked(x, y, map, objects) {
-            let mut item = match item_choices[item_dist.sample(&mut rng)] {
-                Item::Heal => {
-                    let mut object = Object::new(x, y, '!', VIOLET, "healing potion", false);
-                    object.item = Some(Item::Heal);
-                    object
-                }
-                Item::Lightning => {
-                    let mut object =
-                        Object::new(x, y, '#', LIGHT_YELLOW, "scroll of lightning bolt", false);
-                    object.item = Some(Item::Lightning);
-                    object
-                }
-                Item::Fireball => {
-                    let mut object =
-                        Object::new(x, y, '#', LIGHT_YELLOW, "scroll of fireball", false);
-                    object.item = Some(Item::Fireball);
-                    object
-                }
-                Item::Confuse => {
-                    let mut object =
-                        Object::new(x, y, '#', LIGHT_YELLOW, "scroll of confusion", false);
-                    object.item = Some(Item::Confuse);
-                    object
-                }
-                Item::Blink => {
-                    let mut object = Object::new(x, y, '#', LIGHT_YELLOW, "scroll of blink", false);
-                    object.item = Some(Item::Blink);
-                    object
-                }
-                Item::Freeze => {
-                    let mut object =
-                        Object::new(x, y, '#', LIGHT_YELLOW, "scroll of freeze", false);
-                    object.item = Some(Item::Freeze);
-                    object
-                }
-                Item::Sword => {
-                    let mut object = Object::new(x, y, '/', SKY, "sword", false);
-                    object.item = Some(Item::Sword);
-                    object.equipment = Some(Equipment {
-                        slot: Slot::RightHand,
-                        equipped: false,
-                        max_hp_bonus: 0,
-                        defense_bonus: 0,
-                        power_bonus: 3,
-                    });
-                    object
-                }
-                Item::Shield => {
-                    let mut object = Object::new(x, y, '[', DARKER_ORANGE, "shield", false);
-                    object.item = Some(Item::Shield);
-                    object.equipment = Some(Equipment {
-                        slot: Slot::LeftHand,
-                        equipped: false,
-                        max_hp_bonus: 0,
-                        defense_bonus: 1,
-                        power_bonus: 0,
-                    });
-                    object
-                }
-            };
-            item.always_visible = true;
-            objects.push(item);
+            let item = generate_item(item_choices[item_dist.sample(&mut rng)].item, x, y, level);
+            objects.insert(item);
         }
     }
 }
@@ -3,13 +3,15 @@ use std::error::Error;
 use std::fs::File;
 use std::io::{Read, Write};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct PermanentUpgrades {
     pub echoes: i32,
 
     pub bonus_hp: i32,
     pub bonus_power: i32,
     pub bonus_defense: i32,
+    #[serde(default)]
+    pub companion: bool,
 }
 
 impl PermanentUpgrades {
@@ -19,6 +21,7 @@ impl PermanentUpgrades {
             bonus_hp: 0,
             bonus_power: 0,
             bonus_defense: 0,
+            companion: false,
         }
     }
 }
@@ -0,0 +1,510 @@
+use crate::affix::generate_item;
+use crate::boss::{Boss, BossPhase};
+use crate::constants::*;
+use crate::game::Game;
+use crate::item::{add_to_inventory, create_gold, free_slot, item_templates};
+use crate::map::{check_depth_table, from_dungeon_level};
+use crate::object::{
+    Ai, DeathCallback, Equipment, Faction, Fighter, Inventory, Item, Object, monster_death,
+};
+use crate::status::StatusEffect;
+use rand::Rng;
+use rand::distr::Distribution;
+use rand::distr::weighted::WeightedIndex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+use std::sync::OnceLock;
+use tcod::colors::*;
+
+static MONSTERS: OnceLock<Vec<MonsterTemplate>> = OnceLock::new();
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MonsterTemplate {
+    pub name: String,
+    pub glyph: char,
+    pub color: [u8; 3],
+    pub hp: i32,
+    pub defense: i32,
+    pub power: i32,
+    pub xp: i32,
+    #[serde(default)]
+    pub on_hit: Option<StatusEffect>,
+    pub ai: Ai,
+    pub faction: Faction,
+    #[serde(default = "default_min_depth")]
+    pub min_depth: u32,
+    #[serde(default)]
+    pub max_depth: Option<u32>,
+    #[serde(default)]
+    pub spawn_weight: Vec<(u32, u32)>,
+    // a template with pack members spawns as the leader of a group of them
+    #[serde(default)]
+    pub pack: Option<String>,
+    #[serde(default)]
+    pub loot: Option<Loot>,
+    #[serde(default)]
+    pub picks_up_items: bool,
+    // (item, percent chance) for gear the monster spawns wearing
+    #[serde(default)]
+    pub equipment: Vec<(Item, u32)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Loot {
+    // percent chance to drop one item from the pool
+    pub chance: u32,
+    #[serde(default)]
+    pub items: Vec<(Item, u32)>,
+    #[serde(default)]
+    pub gold: (i32, i32),
+}
+
+fn default_min_depth() -> u32 {
+    1
+}
+
+impl MonsterTemplate {
+    pub fn spawn_weight_at(&self, level: u32) -> u32 {
+        let in_range =
+            level >= self.min_depth && self.max_depth.is_none_or(|max_depth| level <= max_depth);
+        if in_range {
+            from_dungeon_level(&self.spawn_weight, level)
+        } else {
+            0
+        }
+    }
+
+    pub fn spawns_at(&self, level: u32) -> bool {
+        self.spawn_weight_at(level) > 0
+    }
+}
+
+pub fn load_monsters(path: &str) -> Result<(), Box<dyn Error>> {
+    let mut json = String::new();
+    let mut file = File::open(path)?;
+    file.read_to_string(&mut json)?;
+    let templates = parse_monsters(&json)?;
+    // a second load keeps the templates the game started with
+    let _ = MONSTERS.set(templates);
+    Ok(())
+}
+
+pub fn parse_monsters(json: &str) -> Result<Vec<MonsterTemplate>, Box<dyn Error>> {
+    let templates = serde_json::from_str::<Vec<MonsterTemplate>>(json)?;
+    validate(&templates)?;
+    Ok(templates)
+}
+
+fn validate(templates: &[MonsterTemplate]) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for template in templates {
+        if template.name.is_empty() {
+            return Err("monster with an empty name".into());
+        }
+        if !names.insert(template.name.as_str()) {
+            return Err(format!("monster '{}' is defined twice", template.name));
+        }
+    }
+
+    for template in templates {
+        let name = &template.name;
+        if template.hp <= 0 {
+            return Err(format!("monster '{}' must have positive hp", name));
+        }
+        if template.defense < 0 || template.power < 0 || template.xp < 0 {
+            return Err(format!(
+                "monster '{}' has negative defense, power or xp",
+                name
+            ));
+        }
+        if template.min_depth == 0 {
+            return Err(format!(
+                "monster '{}' has min_depth 0, depths start at 1",
+                name
+            ));
+        }
+        if let Some(max_depth) = template.max_depth.filter(|&max| max < template.min_depth) {
+            return Err(format!(
+                "monster '{}' has max_depth {} below min_depth {}",
+                name, max_depth, template.min_depth
+            ));
+        }
+        check_depth_table(&template.spawn_weight)
+            .map_err(|e| format!("monster '{}' has a bad spawn_weight table: {}", name, e))?;
+        match &template.ai {
+            Ai::Basic | Ai::Summoner { .. } => {}
+            Ai::Ranged { range, .. } if *range <= 0 => {
+                return Err(format!(
+                    "monster '{}' has a non-positive ranged range",
+                    name
+                ));
+            }
+            Ai::Ranged { .. } => {}
+            Ai::Caster { spells, .. } if spells.is_empty() => {
+                return Err(format!("monster '{}' is a caster without spells", name));
+            }
+            Ai::Caster { .. } => {}
+            Ai::Spawner { monster, .. } if !names.contains(monster.as_str()) => {
+                return Err(format!(
+                    "monster '{}' spawns unknown monster '{}'",
+                    name, monster
+                ));
+            }
+            Ai::Spawner { .. } => {}
+            // bosses are placed by level, never drawn from the spawn table
+            Ai::Boss { .. } if !template.spawn_weight.is_empty() => {
+                return Err(format!("boss '{}' has a spawn_weight table", name));
+            }
+            Ai::Boss {
+                phase: BossPhase::Normal,
+                cooldown: 0,
+                ..
+            } => {}
+            ai => {
+                return Err(format!(
+                    "monster '{}' uses {:?}, which cannot be set from a template",
+                    name, ai
+                ));
+            }
+        }
+        if let Some(loot) = &template.loot {
+            if loot.chance > 100 {
+                return Err(format!("monster '{}' has a loot chance above 100", name));
+            }
+            if loot.chance > 0 && loot.items.iter().all(|&(_, weight)| weight == 0) {
+                return Err(format!("monster '{}' has a loot chance but no items", name));
+            }
+            let (min_gold, max_gold) = loot.gold;
+            if min_gold < 0 || max_gold < min_gold {
+                return Err(format!("monster '{}' has a bad gold range", name));
+            }
+        }
+        for &(item, chance) in &template.equipment {
+            if !item.is_equipment() {
+                return Err(format!("monster '{}' cannot wear {:?}", name, item));
+            }
+            if chance > 100 {
+                return Err(format!(
+                    "monster '{}' has a {:?} chance above 100",
+                    name, item
+                ));
+            }
+        }
+        if let Some(member) = template
+            .pack
+            .as_ref()
+            .filter(|member| !names.contains(member.as_str()))
+        {
+            return Err(format!(
+                "monster '{}' leads a pack of unknown monster '{}'",
+                name, member
+            ));
+        }
+    }
+
+    // these are created by name from code rather than from the spawn table
+    let required = Boss::ALL.iter().map(|boss| boss.minion()).chain(["zombie"]);
+    for name in required {
+        if !names.contains(name) {
+            return Err(format!("required monster '{}' is missing", name));
+        }
+    }
+    for boss in Boss::ALL {
+        let count = templates
+            .iter()
+            .filter(|template| matches!(template.ai, Ai::Boss { boss: b, .. } if b == boss))
+            .count();
+        if count != 1 {
+            return Err(format!(
+                "boss {:?} needs exactly one template, found {}",
+                boss, count
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn templates() -> &'static [MonsterTemplate] {
+    MONSTERS.get().expect("monster templates were not loaded")
+}
+
+pub fn template(name: &str) -> Option<&'static MonsterTemplate> {
+    templates().iter().find(|template| template.name == name)
+}
+
+pub fn boss_template(boss: Boss) -> &'static MonsterTemplate {
+    templates()
+        .iter()
+        .find(|template| matches!(template.ai, Ai::Boss { boss: b, .. } if b == boss))
+        .unwrap_or_else(|| panic!("no template for boss {:?}", boss))
+}
+
+pub fn create_monster(name: &str, x: i32, y: i32, level: u32) -> Object {
+    let template = template(name).unwrap_or_else(|| panic!("unknown monster '{}'", name));
+    let [r, g, b] = template.color;
+    let mut monster = Object::new(x, y, template.glyph, Color::new(r, g, b), name, true);
+    let mut fighter = Fighter {
+        base_max_hp: template.hp,
+        hp: template.hp,
+        base_defense: template.defense,
+        base_power: template.power,
+        xp: template.xp,
+        on_death: DeathCallback::Monster,
+        on_hit: template.on_hit,
+    };
+    scale_to_level(&mut fighter, level);
+    monster.insert(fighter);
+    monster.faction = template.faction;
+    monster.insert(template.ai.clone());
+    if let Some(loot) = &template.loot {
+        monster.insert(loot.clone());
+    }
+    monster.insert(Inventory {
+        items: vec![],
+        picks_up_items: template.picks_up_items,
+    });
+    let mut rng = rand::rng();
+    for &(item, chance) in &template.equipment {
+        if rng.random_range(0..100) < chance {
+            wear(&mut monster, generate_item(item, x, y, level));
+        }
+    }
+    monster.alive = true;
+    monster
+}
+
+// puts the item on if its slot is free, without the player-facing equip messages
+pub fn wear(monster: &mut Object, mut item: Object) -> bool {
+    let equipment = item.get::<Equipment>().copied();
+    let inventory = monster.inventory_mut();
+    let worn = match equipment.and_then(|equipment| free_slot(&equipment, inventory)) {
+        Some(slot) => {
+            if let Some(equipment) = item.get_mut::<Equipment>() {
+                equipment.slot = slot;
+                equipment.equipped = true;
+            }
+            true
+        }
+        None => false,
+    };
+    add_to_inventory(inventory, item);
+    worn
+}
+
+fn scale_to_level(fighter: &mut Fighter, level: u32) {
+    let levels = level.saturating_sub(1) as i32;
+    fighter.base_max_hp += fighter.base_max_hp * levels * MONSTER_HP_SCALING / 100;
+    fighter.hp = fighter.base_max_hp;
+    fighter.base_power += levels / MONSTER_POWER_LEVELS;
+    fighter.base_defense += levels / MONSTER_DEFENSE_LEVELS;
+    fighter.xp += fighter.xp * levels * MONSTER_XP_SCALING / 100;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Variant {
+    Vicious,
+    Armoured,
+    Hulking,
+    Champion,
+}
+
+impl Variant {
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Variant::Vicious => "vicious",
+            Variant::Armoured => "armoured",
+            Variant::Hulking => "hulking",
+            Variant::Champion => "champion",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Variant::Vicious => LIGHT_RED,
+            Variant::Armoured => LIGHTER_GREY,
+            Variant::Hulking => LIGHT_ORANGE,
+            Variant::Champion => GOLD,
+        }
+    }
+
+    pub fn roll(level: u32) -> Option<Variant> {
+        let mut rng = rand::rng();
+        let roll = rng.random_range(0..100);
+        let champion_chance = from_dungeon_level(CHAMPION_CHANCE, level);
+        let elite_chance = from_dungeon_level(ELITE_CHANCE, level);
+        if roll < champion_chance {
+            Some(Variant::Champion)
+        } else if roll < champion_chance + elite_chance {
+            let elites = [Variant::Vicious, Variant::Armoured, Variant::Hulking];
+            Some(elites[rng.random_range(0..elites.len())])
+        } else {
+            None
+        }
+    }
+
+    pub fn apply(self, monster: &mut Object) {
+        monster.name = format!("{} {}", self.prefix(), monster.name);
+        monster.color = self.color();
+        if let Some(fighter) = monster.get_mut::<Fighter>() {
+            match self {
+                Variant::Vicious => fighter.base_power += fighter.base_power / 2 + 1,
+                Variant::Armoured => fighter.base_defense += 2,
+                Variant::Hulking => fighter.base_max_hp += fighter.base_max_hp / 2,
+                Variant::Champion => {
+                    fighter.base_max_hp *= 2;
+                    fighter.base_power += fighter.base_power / 2 + 1;
+                    fighter.base_defense += 1;
+                }
+            }
+            fighter.hp = fighter.base_max_hp;
+            fighter.xp *= match self {
+                Variant::Champion => CHAMPION_XP_MULTIPLIER,
+                _ => ELITE_XP_MULTIPLIER,
+            };
+            fighter.on_death = DeathCallback::Elite;
+        }
+    }
+}
+
+pub fn elite_death(monster: &mut Object, game: &mut Game) {
+    let name = monster.name.clone();
+    monster_death(monster, game);
+
+    // elites always carry something worth the extra trouble
+    let templates = item_templates();
+    let weights = templates
+        .iter()
+        .map(|template| template.spawn_weight_at(game.dungeon_level).max(1));
+    if let Ok(dist) = WeightedIndex::new(weights) {
+        let (x, y) = monster.pos();
+        let item = generate_item(
+            templates[dist.sample(&mut rand::rng())].item,
+            x,
+            y,
+            game.dungeon_level,
+        );
+        game.messages.add(
+            format!(
+                "The {} drops {}.",
+                name,
+                game.identification.described(&item)
+            ),
+            LIGHT_GREEN,
+        );
+        game.spawn_queue.push(item);
+    }
+}
+
+pub fn drop_loot(monster: &mut Object, game: &mut Game) {
+    let (x, y) = monster.pos();
+    if let Some(inventory) = monster.remove::<Inventory>() {
+        for mut item in inventory.items {
+            if let Some(equipment) = item.get_mut::<Equipment>() {
+                equipment.equipped = false;
+            }
+            item.set_pos(x, y);
+            game.spawn_queue.push(item);
+        }
+    }
+
+    let Some(loot) = monster.remove::<Loot>() else {
+        return;
+    };
+    let mut rng = rand::rng();
+    let dist = WeightedIndex::new(loot.items.iter().map(|&(_, weight)| weight));
+    match dist {
+        Ok(dist) if rng.random_range(0..100) < loot.chance => {
+            let item = loot.items[dist.sample(&mut rng)].0;
+            game.spawn_queue
+                .push(generate_item(item, x, y, game.dungeon_level));
+        }
+        _ => {}
+    }
+    let (min_gold, max_gold) = loot.gold;
+    let amount = rng.random_range(min_gold..=max_gold);
+    if amount > 0 {
+        game.spawn_queue.push(create_gold(amount, x, y));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    const MONSTERS_JSON: &str = include_str!("../monsters.json");
+
+    fn parse_edited(edit: impl FnOnce(&mut Vec<Value>)) -> String {
+        let mut monsters: Vec<Value> = serde_json::from_str(MONSTERS_JSON).unwrap();
+        edit(&mut monsters);
+        let json = serde_json::to_string(&monsters).unwrap();
+        parse_monsters(&json).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn every_boss_has_a_template() {
+        MONSTERS.get_or_init(|| parse_monsters(MONSTERS_JSON).unwrap());
+        for boss in Boss::ALL {
+            let template = boss_template(boss);
+            assert!(
+                (1..=10).all(|level| !template.spawns_at(level)),
+                "{}",
+                template.name
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let error = parse_edited(|monsters| {
+            monsters[0]["hpp"] = json!(20);
+        });
+        assert!(error.contains("unknown field `hpp`"), "{}", error);
+    }
+
+    #[test]
+    fn missing_minions_are_rejected() {
+        let error = parse_edited(|monsters| {
+            monsters.retain(|monster| monster["name"] != "skeleton");
+        });
+        assert_eq!(error, "required monster 'skeleton' is missing");
+    }
+
+    #[test]
+    fn bad_depth_tables_are_rejected() {
+        let error = parse_edited(|monsters| {
+            monsters[0]["spawn_weight"] = json!([[0, 10]]);
+        });
+        assert!(
+            error.ends_with("has a bad spawn_weight table: depths start at 1"),
+            "{}",
+            error
+        );
+
+        let error = parse_edited(|monsters| {
+            monsters[0]["spawn_weight"] = json!([[4, 10], [2, 20]]);
+        });
+        assert!(
+            error.ends_with("depths must be strictly increasing"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn bosses_never_spawn_at_random() {
+        let error = parse_edited(|monsters| {
+            let boss = monsters
+                .iter_mut()
+                .find(|monster| monster["ai"]["Boss"]["boss"] == "DemonLord")
+                .unwrap();
+            boss["spawn_weight"] = json!([[1, 10]]);
+        });
+        assert_eq!(error, "boss 'Xul the Demon Lord' has a spawn_weight table");
+    }
+}
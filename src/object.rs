@@ -1,11 +1,19 @@
+use crate::boss::{Boss, BossPhase, boss_death};
+use crate::component::{Component, Components};
+use crate::constants::OFF_HAND_POWER_PERCENT;
+use crate::entity::{EntityId, Objects};
 use crate::game::{Game, Messages};
-use crate::item::get_equipped_in_slot;
+use crate::item::{add_to_inventory, fits_in_inventory, free_slot};
 use crate::meta::{self, PermanentUpgrades};
+use crate::monster::{drop_loot, elite_death};
+use crate::projectile::Projectile;
+use crate::spell::Spell;
+use crate::status::{StatusEffect, StatusKind, apply_status};
 use serde::{Deserialize, Serialize};
 use tcod::colors::*;
 use tcod::console::*;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Object {
     pub x: i32,
     pub y: i32,
@@ -14,33 +22,51 @@ pub struct Object {
     pub name: String,
     pub blocks: bool,
     pub alive: bool,
-    pub fighter: Option<Fighter>,
-    pub ai: Option<Ai>,
-    pub item: Option<Item>,
     pub always_visible: bool,
     pub level: i32,
-    pub equipment: Option<Equipment>,
+    pub components: Components,
+    pub status_effects: Vec<StatusEffect>,
+    pub faction: Faction,
 }
 
 impl Object {
     pub fn new(x: i32, y: i32, char: char, color: Color, name: &str, blocks: bool) -> Self {
         Object {
-            x: x,
-            y: y,
-            char: char,
-            color: color,
+            x,
+            y,
+            char,
+            color,
             name: name.into(),
-            blocks: blocks,
+            blocks,
             alive: false,
-            fighter: None,
-            ai: None,
-            item: None,
             always_visible: false,
             level: 1,
-            equipment: None,
+            components: Components::default(),
+            status_effects: vec![],
+            faction: Faction::Neutral,
         }
     }
 
+    pub fn get<T: Component>(&self) -> Option<&T> {
+        self.components.get::<T>()
+    }
+
+    pub fn get_mut<T: Component>(&mut self) -> Option<&mut T> {
+        self.components.get_mut::<T>()
+    }
+
+    pub fn has<T: Component>(&self) -> bool {
+        self.components.has::<T>()
+    }
+
+    pub fn insert<T: Component>(&mut self, component: T) {
+        self.components.insert(component)
+    }
+
+    pub fn remove<T: Component>(&mut self) -> Option<T> {
+        self.components.remove::<T>()
+    }
+
     pub fn draw(&self, con: &mut dyn Console) {
         con.set_default_foreground(self.color);
         con.put_char(self.x, self.y, self.char, BackgroundFlag::None);
@@ -57,25 +83,25 @@ impl Object {
     }
 
     pub fn take_damage(&mut self, damage: i32, game: &mut Game) -> Option<i32> {
-        if let Some(fighter) = self.fighter.as_mut() {
-            if damage > 0 {
-                fighter.hp -= damage;
-            }
+        if let Some(fighter) = self.get_mut::<Fighter>()
+            && damage > 0
+        {
+            fighter.hp -= damage;
         }
 
-        if let Some(fighter) = self.fighter {
-            if fighter.hp <= 0 {
-                self.alive = false;
-                fighter.on_death.callback(self, game);
-                return Some(fighter.xp);
-            }
+        if let Some(&fighter) = self.get::<Fighter>()
+            && fighter.hp <= 0
+        {
+            self.alive = false;
+            fighter.on_death.callback(self, game);
+            return Some(fighter.xp);
         }
         None
     }
 
-    pub fn heal(&mut self, amount: i32, game: &Game) {
-        let max_hp = self.max_hp(game);
-        if let Some(ref mut fighter) = self.fighter {
+    pub fn heal(&mut self, amount: i32) {
+        let max_hp = self.max_hp();
+        if let Some(fighter) = self.get_mut::<Fighter>() {
             fighter.hp += amount;
             if fighter.hp > max_hp {
                 fighter.hp = max_hp;
@@ -83,8 +109,9 @@ impl Object {
         }
     }
 
-    pub fn attack(&mut self, target: &mut Object, game: &mut Game) {
-        let damage = self.power(game) - target.defense(game);
+    pub fn attack(&mut self, target: &mut Object, game: &mut Game) -> Option<i32> {
+        // fire burns through armour
+        let damage = (self.power() - target.defense()).max(0) + self.fire_damage();
         if damage > 0 {
             game.messages.add(
                 format!(
@@ -93,9 +120,13 @@ impl Object {
                 ),
                 WHITE,
             );
-            if let Some(xp) = target.take_damage(damage, game) {
-                self.fighter.as_mut().unwrap().xp += xp;
+            let xp = target.take_damage(damage, game);
+            if xp.is_none()
+                && let Some(effect) = self.get::<Fighter>().and_then(|f| f.on_hit)
+            {
+                apply_status(target, effect, game);
             }
+            xp
         } else {
             game.messages.add(
                 format!(
@@ -104,9 +135,41 @@ impl Object {
                 ),
                 WHITE,
             );
+            None
         }
     }
 
+    pub fn is_player(&self) -> bool {
+        self.get::<Fighter>()
+            .is_some_and(|f| f.on_death == DeathCallback::Player)
+    }
+
+    pub fn is_companion(&self) -> bool {
+        matches!(self.get::<Ai>(), Some(Ai::Companion { .. }))
+    }
+
+    pub fn is_boss(&self) -> bool {
+        matches!(self.get::<Ai>(), Some(Ai::Boss { .. }))
+    }
+
+    pub fn is_charmable(&self) -> bool {
+        !matches!(
+            self.get::<Ai>(),
+            Some(Ai::Boss { .. }) | Some(Ai::Spawner { .. })
+        )
+    }
+
+    pub fn is_corpse(&self) -> bool {
+        !self.alive
+            && !self.has::<Fighter>()
+            && !self.has::<Item>()
+            && self.name.starts_with("remains of ")
+    }
+
+    pub fn has_status(&self, kind: StatusKind) -> bool {
+        self.status_effects.iter().any(|e| e.kind == kind)
+    }
+
     pub fn pos(&self) -> (i32, i32) {
         (self.x, self.y)
     }
@@ -117,20 +180,27 @@ impl Object {
     }
 
     pub fn equip(&mut self, messages: &mut Messages) {
-        if self.item.is_none() {
+        if !self.has::<Item>() {
             messages.add(
                 format!("Can't equip {:?} because it's not an Item.", self),
                 RED,
             );
             return;
         };
-        if let Some(ref mut equipment) = self.equipment {
+        if let Some(equipment) = self.components.get_mut::<Equipment>() {
             if !equipment.equipped {
                 equipment.equipped = true;
                 messages.add(
                     format!("Equipped {} on {}.", self.name, equipment.slot),
                     LIGHT_GREEN,
                 );
+                if equipment.cursed {
+                    equipment.curse_known = true;
+                    messages.add(
+                        format!("The {} is cursed! You cannot take it off.", self.name),
+                        RED,
+                    );
+                }
             }
         } else {
             messages.add(
@@ -140,15 +210,30 @@ impl Object {
         }
     }
 
-    pub fn dequip(&mut self, messages: &mut Messages) {
-        if self.item.is_none() {
+    pub fn equip_in(&mut self, slot: Slot, messages: &mut Messages) {
+        if let Some(equipment) = self.get_mut::<Equipment>() {
+            equipment.slot = slot;
+        }
+        self.equip(messages);
+    }
+
+    pub fn dequip(&mut self, messages: &mut Messages) -> bool {
+        if !self.has::<Item>() {
             messages.add(
                 format!("Can't dequip {:?} because it's not an Item.", self),
                 RED,
             );
-            return;
+            return false;
         };
-        if let Some(ref mut equipment) = self.equipment {
+        if let Some(equipment) = self.components.get_mut::<Equipment>() {
+            if equipment.equipped && equipment.cursed {
+                equipment.curse_known = true;
+                messages.add(
+                    format!("You can't remove the {}, it is cursed!", self.name),
+                    RED,
+                );
+                return false;
+            }
             if equipment.equipped {
                 equipment.equipped = false;
                 messages.add(
@@ -161,73 +246,149 @@ impl Object {
                 format!("Can't dequip {:?} because it's not an Equipment.", self),
                 RED,
             );
+            return false;
         }
+        true
     }
 
-    pub fn power(&self, game: &Game) -> i32 {
-        let base_power = self.fighter.map_or(0, |f| f.base_power);
-        let bonus: i32 = self
-            .get_all_equipped(game)
+    pub fn is_stuck(&self) -> bool {
+        self.get::<Equipment>()
+            .is_some_and(|e| e.equipped && e.cursed)
+    }
+
+    pub fn power(&self) -> i32 {
+        let base_power = self.get::<Fighter>().map_or(0, |f| f.base_power);
+        base_power + self.power_bonus()
+    }
+
+    pub fn power_bonus(&self) -> i32 {
+        let equipped = self.get_all_equipped();
+        let dual_wielding = equipped
             .iter()
-            .map(|e| e.power_bonus)
-            .sum();
-        base_power + bonus
+            .filter(|e| e.grip == Grip::OneHanded)
+            .count()
+            > 1;
+        equipped
+            .iter()
+            .map(|e| {
+                if dual_wielding && e.grip == Grip::OneHanded && e.slot == Slot::LeftHand {
+                    e.power_bonus * OFF_HAND_POWER_PERCENT / 100
+                } else {
+                    e.power_bonus
+                }
+            })
+            .sum()
+    }
+
+    pub fn fire_damage(&self) -> i32 {
+        self.get_all_equipped().iter().map(|e| e.fire_damage).sum()
     }
 
-    pub fn defense(&self, game: &Game) -> i32 {
-        let base_defense = self.fighter.map_or(0, |f| f.base_defense);
+    pub fn defense(&self) -> i32 {
+        let base_defense = self.get::<Fighter>().map_or(0, |f| f.base_defense);
         let bonus: i32 = self
-            .get_all_equipped(game)
+            .get_all_equipped()
             .iter()
             .map(|e| e.defense_bonus)
             .sum();
         base_defense + bonus
     }
 
-    pub fn max_hp(&self, game: &Game) -> i32 {
-        let base_max_hp = self.fighter.map_or(0, |f| f.base_max_hp);
-        let bonus: i32 = self
-            .get_all_equipped(game)
-            .iter()
-            .map(|e| e.max_hp_bonus)
-            .sum();
+    pub fn max_hp(&self) -> i32 {
+        let base_max_hp = self.get::<Fighter>().map_or(0, |f| f.base_max_hp);
+        let bonus: i32 = self.get_all_equipped().iter().map(|e| e.max_hp_bonus).sum();
         base_max_hp + bonus
     }
 
-    pub fn get_all_equipped(&self, game: &Game) -> Vec<Equipment> {
-        if self.name == "player" {
-            game.inventory
-                .iter()
-                .filter(|item| item.equipment.map_or(false, |e| e.equipped))
-                .map(|item| item.equipment.unwrap())
-                .collect()
-        } else {
-            vec![]
+    pub fn get_all_equipped(&self) -> Vec<Equipment> {
+        self.inventory()
+            .iter()
+            .filter_map(|item| item.get::<Equipment>())
+            .filter(|equipment| equipment.equipped)
+            .copied()
+            .collect()
+    }
+
+    pub fn count(&self) -> i32 {
+        self.get::<Stack>().map_or(1, |stack| stack.count)
+    }
+
+    pub fn set_count(&mut self, count: i32) {
+        if let Some(stack) = self.get_mut::<Stack>() {
+            stack.count = count;
+        }
+    }
+
+    pub fn stacks_with(&self, other: &Object) -> bool {
+        self.has::<Stack>()
+            && other.has::<Stack>()
+            && self.name == other.name
+            && self.get::<Item>() == other.get::<Item>()
+    }
+
+    pub fn inventory(&self) -> &[Object] {
+        self.get::<Inventory>()
+            .map_or(&[], |inventory| inventory.items.as_slice())
+    }
+
+    pub fn inventory_mut(&mut self) -> &mut Vec<Object> {
+        if !self.has::<Inventory>() {
+            self.insert(Inventory::default());
+        }
+        &mut self.get_mut::<Inventory>().unwrap().items
+    }
+}
+
+pub fn grant_xp(killer_id: EntityId, xp: i32, game: &mut Game, objects: &mut Objects) {
+    if killer_id == objects.player_id() {
+        game.messages
+            .add(format!("You gain {} experience points.", xp), ORANGE);
+    } else if objects[killer_id].faction == Faction::Player {
+        game.messages.add(
+            format!(
+                "You gain {} experience points from the kill of your ally {}.",
+                xp, objects[killer_id].name
+            ),
+            ORANGE,
+        );
+    } else {
+        return;
+    }
+    if let Some(fighter) = objects.player_mut().get_mut::<Fighter>() {
+        fighter.xp += xp;
+    }
+}
+
+pub fn plural(name: &str) -> String {
+    for separator in [" of ", " labelled "] {
+        if let Some((head, tail)) = name.split_once(separator) {
+            return format!("{}s{}{}", head, separator, tail);
         }
     }
+    format!("{}s", name)
 }
 
-pub fn pick_item_up(object_id: usize, game: &mut Game, objects: &mut Vec<Object>) {
-    if game.inventory.len() >= 26 {
+pub fn pick_item_up(object_id: EntityId, game: &mut Game, objects: &mut Objects) {
+    if !fits_in_inventory(objects.player().inventory(), &objects[object_id]) {
         game.messages.add(
             format!(
                 "Your inventory is full, cannot pick up {}.",
-                objects[object_id].name
+                game.identification.name(&objects[object_id])
             ),
             RED,
         );
     } else {
-        let item = objects.swap_remove(object_id);
-        game.messages
-            .add(format!("You picked up a {}!", item.name), GREEN);
-        let index = game.inventory.len();
-        let slot = item.equipment.map(|e| e.slot);
-        game.inventory.push(item);
-
-        if let Some(slot) = slot {
-            if get_equipped_in_slot(slot, &game.inventory).is_none() {
-                game.inventory[index].equip(&mut game.messages);
-            }
+        let item = objects.remove(object_id).unwrap();
+        game.messages.add(
+            format!("You picked up {}!", game.identification.described(&item)),
+            GREEN,
+        );
+        let inventory = objects.player_mut().inventory_mut();
+        let equipment = item.get::<Equipment>().copied();
+        let index = add_to_inventory(inventory, item);
+
+        if let Some(slot) = equipment.and_then(|equipment| free_slot(&equipment, inventory)) {
+            inventory[index].equip_in(slot, &mut game.messages);
         }
     }
 }
@@ -240,12 +401,15 @@ pub struct Fighter {
     pub base_power: i32,
     pub xp: i32,
     pub on_death: DeathCallback,
+    pub on_hit: Option<StatusEffect>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum DeathCallback {
     Player,
     Monster,
+    Boss,
+    Elite,
 }
 
 impl DeathCallback {
@@ -254,6 +418,8 @@ impl DeathCallback {
         let callback: fn(&mut Object, &mut Game) = match self {
             Player => player_death,
             Monster => monster_death,
+            Boss => boss_death,
+            Elite => elite_death,
         };
         callback(object, game)
     }
@@ -262,7 +428,7 @@ impl DeathCallback {
 pub fn player_death(player: &mut Object, game: &mut Game) {
     game.messages.add("You died!", RED);
 
-    let xp = player.fighter.map_or(0, |f| f.xp);
+    let xp = player.get::<Fighter>().map_or(0, |f| f.xp);
     let echoes_earned = (xp / 10) + (game.dungeon_level * 25) as i32;
 
     if echoes_earned > 0 {
@@ -283,36 +449,80 @@ pub fn player_death(player: &mut Object, game: &mut Game) {
 }
 
 pub fn monster_death(monster: &mut Object, game: &mut Game) {
-    game.messages.add(
-        format!(
-            "{} is dead! You gain {} experience points.",
-            monster.name,
-            monster.fighter.unwrap().xp
-        ),
-        ORANGE,
-    );
+    drop_loot(monster, game);
+    game.messages
+        .add(format!("{} is dead!", monster.name), ORANGE);
     monster.char = '%';
     monster.color = DARK_RED;
     monster.blocks = false;
-    monster.fighter = None;
-    monster.ai = None;
+    monster.remove::<Fighter>();
+    monster.remove::<Ai>();
     monster.name = format!("remains of {}", monster.name);
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Faction {
+    Neutral,
+    Player,
+    Orcs,
+    Undead,
+    Cultists,
+    Beasts,
+}
+
+impl Faction {
+    pub fn is_hostile_to(self, other: Faction) -> bool {
+        use Faction::*;
+        match (self, other) {
+            (Neutral, _) | (_, Neutral) => false,
+            (Undead, Cultists) | (Cultists, Undead) => false,
+            (a, b) => a != b,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Ai {
     Basic,
-    Confused {
-        previous_ai: Box<Ai>,
-        num_turns: i32,
+    Ranged {
+        range: i32,
+        projectile: Projectile,
+    },
+    Caster {
+        spells: Vec<Spell>,
+        cooldown: i32,
+    },
+    Companion {
+        order: Order,
+    },
+    Pack {
+        leader: EntityId,
     },
-    Frozen {
-        previous_ai: Box<Ai>,
-        num_turns: i32,
+    Fleeing {
+        turns: i32,
+    },
+    Boss {
+        boss: Boss,
+        phase: BossPhase,
+        cooldown: i32,
+    },
+    Summoner {
+        cooldown: i32,
+    },
+    Spawner {
+        monster: String,
+        timer: i32,
     },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Order {
+    Follow,
+    Stay,
+    Attack(EntityId),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Item {
     Heal,
     Lightning,
@@ -320,17 +530,174 @@ pub enum Item {
     Fireball,
     Blink,
     Freeze,
+    Charm,
+    Identify,
+    Recharge,
+    RemoveCurse,
+    Wand(Spell),
     Sword,
     Shield,
+    Greatsword,
+    Bow,
+    Crossbow,
+    Arrow,
+    Bolt,
+    Helmet,
+    LeatherArmour,
+    ChainMail,
+    Cloak,
+    Gauntlets,
+    Boots,
+    ProtectionRing,
+    StrengthRing,
+    VitalityRing,
+    Amulet,
+}
+
+impl Item {
+    pub const ALL: [Item; 31] = [
+        Item::Heal,
+        Item::Lightning,
+        Item::Confuse,
+        Item::Fireball,
+        Item::Blink,
+        Item::Freeze,
+        Item::Charm,
+        Item::Identify,
+        Item::Recharge,
+        Item::RemoveCurse,
+        Item::Wand(Spell::Lightning),
+        Item::Wand(Spell::Fireball),
+        Item::Wand(Spell::Confuse),
+        Item::Wand(Spell::Freeze),
+        Item::Sword,
+        Item::Shield,
+        Item::Greatsword,
+        Item::Bow,
+        Item::Crossbow,
+        Item::Arrow,
+        Item::Bolt,
+        Item::Helmet,
+        Item::LeatherArmour,
+        Item::ChainMail,
+        Item::Cloak,
+        Item::Gauntlets,
+        Item::Boots,
+        Item::ProtectionRing,
+        Item::StrengthRing,
+        Item::VitalityRing,
+        Item::Amulet,
+    ];
+
+    pub fn is_equipment(self) -> bool {
+        matches!(
+            self,
+            Item::Sword
+                | Item::Shield
+                | Item::Greatsword
+                | Item::Bow
+                | Item::Crossbow
+                | Item::Arrow
+                | Item::Bolt
+                | Item::Helmet
+                | Item::LeatherArmour
+                | Item::ChainMail
+                | Item::Cloak
+                | Item::Gauntlets
+                | Item::Boots
+                | Item::ProtectionRing
+                | Item::StrengthRing
+                | Item::VitalityRing
+                | Item::Amulet
+        )
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Equipment {
     pub slot: Slot,
+    #[serde(default)]
     pub equipped: bool,
+    #[serde(default)]
     pub max_hp_bonus: i32,
+    #[serde(default)]
     pub defense_bonus: i32,
+    #[serde(default)]
     pub power_bonus: i32,
+    #[serde(default)]
+    pub fire_damage: i32,
+    #[serde(default)]
+    pub grip: Grip,
+    #[serde(default)]
+    pub cursed: bool,
+    // whether the player has found out if it is cursed or not
+    #[serde(default)]
+    pub curse_known: bool,
+}
+
+impl Equipment {
+    // a two-handed weapon sits in the right hand but fills both
+    pub fn fills(&self, slot: Slot) -> bool {
+        self.slot == slot || (self.grip == Grip::TwoHanded && slot.is_hand())
+    }
+
+    pub fn slots(&self) -> Vec<Slot> {
+        match self.grip {
+            Grip::OneHanded => vec![Slot::RightHand, Slot::LeftHand],
+            Grip::TwoHanded => vec![Slot::RightHand],
+            Grip::None => std::iter::once(self.slot)
+                .chain(self.slot.paired())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Grip {
+    #[default]
+    None,
+    OneHanded,
+    TwoHanded,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Launcher {
+    pub ammo: Item,
+    pub range: i32,
+    pub power: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stack {
+    pub count: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Charges {
+    pub current: i32,
+    pub max: i32,
+    // a wand that was recharged before may break on the next try
+    #[serde(default)]
+    pub recharged: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Gold {
+    pub amount: i32,
+}
+
+// marks a creature as one of a nest's brood, whatever name it ends up with
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Spawned {
+    pub nest: EntityId,
+}
+
+// everything a creature carries, equipped or not, dropped again where it dies
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Inventory {
+    pub items: Vec<Object>,
+    #[serde(default)]
+    pub picks_up_items: bool,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
@@ -338,6 +705,45 @@ pub enum Slot {
     LeftHand,
     RightHand,
     Head,
+    Body,
+    Cloak,
+    Hands,
+    Feet,
+    LeftRing,
+    RightRing,
+    Amulet,
+    Ranged,
+    Quiver,
+}
+
+impl Slot {
+    pub const ALL: [Slot; 12] = [
+        Slot::Head,
+        Slot::Amulet,
+        Slot::Cloak,
+        Slot::Body,
+        Slot::Hands,
+        Slot::RightHand,
+        Slot::LeftHand,
+        Slot::RightRing,
+        Slot::LeftRing,
+        Slot::Feet,
+        Slot::Ranged,
+        Slot::Quiver,
+    ];
+
+    pub fn is_hand(self) -> bool {
+        matches!(self, Slot::LeftHand | Slot::RightHand)
+    }
+
+    // the other slot an item may go in when its own is taken
+    pub fn paired(self) -> Option<Slot> {
+        match self {
+            Slot::LeftRing => Some(Slot::RightRing),
+            Slot::RightRing => Some(Slot::LeftRing),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for Slot {
@@ -346,6 +752,15 @@ impl std::fmt::Display for Slot {
             Slot::LeftHand => write!(f, "left hand"),
             Slot::RightHand => write!(f, "right hand"),
             Slot::Head => write!(f, "head"),
+            Slot::Body => write!(f, "body"),
+            Slot::Cloak => write!(f, "shoulders"),
+            Slot::Hands => write!(f, "hands"),
+            Slot::Feet => write!(f, "feet"),
+            Slot::LeftRing => write!(f, "left ring finger"),
+            Slot::RightRing => write!(f, "right ring finger"),
+            Slot::Amulet => write!(f, "neck"),
+            Slot::Ranged => write!(f, "ranged slot"),
+            Slot::Quiver => write!(f, "quiver"),
         }
     }
 }
@@ -0,0 +1,133 @@
+use crate::entity::{EntityId, Objects};
+use crate::game::{Game, Tcod};
+use crate::map::Map;
+use crate::object::grant_xp;
+use crate::ui::animate_projectile;
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+use tcod::line::Line;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Projectile {
+    Arrow,
+    MagicMissile,
+    Thrown,
+}
+
+impl Projectile {
+    pub fn name(self) -> &'static str {
+        match self {
+            Projectile::Arrow => "arrow",
+            Projectile::MagicMissile => "magic missile",
+            Projectile::Thrown => "missile",
+        }
+    }
+
+    pub fn glyph(self, dx: i32, dy: i32) -> char {
+        match self {
+            Projectile::Arrow if dx.abs() > 2 * dy.abs() => '-',
+            Projectile::Arrow if dy.abs() > 2 * dx.abs() => '|',
+            Projectile::Arrow if dx.signum() == dy.signum() => '\\',
+            Projectile::Arrow => '/',
+            Projectile::MagicMissile => '*',
+            Projectile::Thrown => ')',
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Projectile::Arrow => LIGHT_SEPIA,
+            Projectile::MagicMissile => LIGHT_MAGENTA,
+            Projectile::Thrown => LIGHT_GREY,
+        }
+    }
+}
+
+pub fn trace_path(
+    from: (i32, i32),
+    to: (i32, i32),
+    map: &Map,
+    objects: &Objects,
+) -> (Vec<(i32, i32)>, Option<EntityId>) {
+    let mut path = vec![];
+    for (x, y) in Line::new(from, to) {
+        if map[x as usize][y as usize].blocked {
+            return (path, None);
+        }
+        path.push((x, y));
+        let hit = objects.position(|object| object.blocks && object.pos() == (x, y));
+        if hit.is_some() {
+            return (path, hit);
+        }
+    }
+    (path, None)
+}
+
+pub fn has_line_of_fire(
+    shooter_id: EntityId,
+    target_id: EntityId,
+    map: &Map,
+    objects: &Objects,
+) -> bool {
+    let (_, hit) = trace_path(
+        objects[shooter_id].pos(),
+        objects[target_id].pos(),
+        map,
+        objects,
+    );
+    hit == Some(target_id)
+}
+
+pub fn fire_projectile(
+    shooter_id: EntityId,
+    target: (i32, i32),
+    projectile: Projectile,
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut Objects,
+) {
+    let origin = objects[shooter_id].pos();
+    let (path, hit) = trace_path(origin, target, &game.map, objects);
+    animate_projectile(tcod, game, objects, origin, &path, projectile);
+
+    match hit {
+        Some(hit_id) => {
+            let damage = objects[shooter_id].power() - objects[hit_id].defense();
+            if damage > 0 {
+                game.messages.add(
+                    format!(
+                        "The {} from {} hits {} for {} hit points.",
+                        projectile.name(),
+                        objects[shooter_id].name,
+                        objects[hit_id].name,
+                        damage
+                    ),
+                    WHITE,
+                );
+                if let Some(xp) = objects[hit_id].take_damage(damage, game) {
+                    grant_xp(shooter_id, xp, game, objects);
+                }
+            } else {
+                game.messages.add(
+                    format!(
+                        "The {} from {} glances off {}.",
+                        projectile.name(),
+                        objects[shooter_id].name,
+                        objects[hit_id].name
+                    ),
+                    WHITE,
+                );
+            }
+        }
+        None => {
+            game.messages.add(
+                format!(
+                    "The {} from {} hits the wall.",
+                    projectile.name(),
+                    objects[shooter_id].name
+                ),
+                WHITE,
+            );
+        }
+    }
+}
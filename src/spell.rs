@@ -0,0 +1,214 @@
+use crate::constants::*;
+use crate::entity::{EntityId, Objects};
+use crate::game::Game;
+use crate::map::is_blocked;
+use crate::object::{Ai, Fighter, Order, grant_xp};
+use crate::status::{StatusEffect, StatusKind, apply_status};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Spell {
+    Heal,
+    Lightning,
+    Confuse,
+    Fireball,
+    Blink,
+    Freeze,
+    Charm,
+}
+
+impl Spell {
+    pub fn name(self) -> &'static str {
+        match self {
+            Spell::Heal => "healing",
+            Spell::Lightning => "lightning bolt",
+            Spell::Confuse => "confusion",
+            Spell::Fireball => "fireball",
+            Spell::Blink => "blink",
+            Spell::Freeze => "freeze",
+            Spell::Charm => "charm",
+        }
+    }
+}
+
+pub fn heal(target_id: EntityId, amount: i32, game: &mut Game, objects: &mut Objects) {
+    if target_id == objects.player_id() {
+        game.messages
+            .add("Your wounds start to feel better!", LIGHT_VIOLET);
+    } else {
+        game.messages.add(
+            format!("The wounds of {} start to close.", objects[target_id].name),
+            LIGHT_VIOLET,
+        );
+    }
+    objects[target_id].heal(amount);
+}
+
+pub fn lightning(caster_id: EntityId, target_id: EntityId, game: &mut Game, objects: &mut Objects) {
+    game.messages.add(
+        format!(
+            "A lightning bolt strikes the {} with a loud thunder! \
+             The damage is {} hit points.",
+            objects[target_id].name, LIGHTNING_DAMAGE
+        ),
+        LIGHT_BLUE,
+    );
+    if let Some(xp) = objects[target_id].take_damage(LIGHTNING_DAMAGE, game) {
+        grant_xp(caster_id, xp, game, objects);
+    }
+}
+
+pub fn confuse(target_id: EntityId, game: &mut Game, objects: &mut Objects) {
+    let effect = StatusEffect::new(StatusKind::Confused, CONFUSE_NUM_TURNS, 0);
+    apply_status(&mut objects[target_id], effect, game);
+}
+
+pub fn freeze(target_id: EntityId, game: &mut Game, objects: &mut Objects) {
+    let effect = StatusEffect::new(StatusKind::Frozen, FREEZE_NUM_TURNS, 0);
+    apply_status(&mut objects[target_id], effect, game);
+}
+
+pub fn charm(caster_id: EntityId, target_id: EntityId, game: &mut Game, objects: &mut Objects) {
+    objects[target_id].faction = objects[caster_id].faction;
+    if caster_id == objects.player_id() {
+        objects[target_id].insert(Ai::Companion {
+            order: Order::Follow,
+        });
+        game.messages.add(
+            format!(
+                "The {} looks at you adoringly, and joins your side!",
+                objects[target_id].name
+            ),
+            LIGHT_PINK,
+        );
+    } else {
+        game.messages.add(
+            format!(
+                "The {} turns on its allies, enthralled by {}!",
+                objects[target_id].name, objects[caster_id].name
+            ),
+            LIGHT_PINK,
+        );
+    }
+}
+
+pub fn fireball(caster_id: EntityId, x: i32, y: i32, game: &mut Game, objects: &mut Objects) {
+    game.messages.add(
+        format!(
+            "The fireball explodes, burning everything within {} tiles!",
+            FIREBALL_RADIUS
+        ),
+        ORANGE,
+    );
+
+    let caster_faction = objects[caster_id].faction;
+    let mut xp_to_gain = 0;
+    for (id, obj) in objects.entries_mut() {
+        if obj.distance(x, y) <= FIREBALL_RADIUS as f32 && obj.has::<Fighter>() {
+            game.messages.add(
+                format!(
+                    "The {} gets burned for {} hit points.",
+                    obj.name, FIREBALL_DAMAGE
+                ),
+                ORANGE,
+            );
+            if let Some(xp) = obj.take_damage(FIREBALL_DAMAGE, game)
+                && id != caster_id
+                && caster_faction.is_hostile_to(obj.faction)
+            {
+                xp_to_gain += xp;
+            }
+        }
+    }
+
+    if xp_to_gain > 0 {
+        grant_xp(caster_id, xp_to_gain, game, objects);
+    }
+}
+
+// the stored magic of a broken wand going off all at once around its holder
+pub fn burst(
+    caster_id: EntityId,
+    spell: Spell,
+    charges: i32,
+    game: &mut Game,
+    objects: &mut Objects,
+) {
+    let (x, y) = objects[caster_id].pos();
+    let caster_faction = objects[caster_id].faction;
+    let targets: Vec<EntityId> = objects
+        .query::<Fighter>()
+        .filter(|&(id, obj, _)| {
+            id != caster_id && obj.alive && obj.distance(x, y) <= WAND_BURST_RADIUS as f32
+        })
+        .map(|(id, _, _)| id)
+        .collect();
+
+    let damage = WAND_BURST_DAMAGE * charges;
+    let mut xp_to_gain = 0;
+    for target_id in targets {
+        match spell {
+            Spell::Lightning | Spell::Fireball => {
+                game.messages.add(
+                    format!(
+                        "The {} is caught in the blast for {} hit points.",
+                        objects[target_id].name, damage
+                    ),
+                    ORANGE,
+                );
+                let target = &mut objects[target_id];
+                if let Some(xp) = target.take_damage(damage, game)
+                    && caster_faction.is_hostile_to(target.faction)
+                {
+                    xp_to_gain += xp;
+                }
+            }
+            Spell::Confuse => confuse(target_id, game, objects),
+            Spell::Freeze => freeze(target_id, game, objects),
+            Spell::Heal => heal(target_id, HEAL_AMOUNT * charges, game, objects),
+            Spell::Blink | Spell::Charm => {}
+        }
+    }
+
+    if xp_to_gain > 0 {
+        grant_xp(caster_id, xp_to_gain, game, objects);
+    }
+}
+
+pub fn blink(caster_id: EntityId, x: i32, y: i32, game: &mut Game, objects: &mut Objects) {
+    if caster_id == objects.player_id() {
+        game.messages
+            .add("You teleport to the new location!", LIGHT_GREEN);
+    } else {
+        game.messages.add(
+            format!(
+                "The {} vanishes in a puff of smoke!",
+                objects[caster_id].name
+            ),
+            LIGHT_GREEN,
+        );
+    }
+    objects[caster_id].set_pos(x, y);
+}
+
+pub fn blink_destination(
+    caster_id: EntityId,
+    threat_id: EntityId,
+    game: &Game,
+    objects: &Objects,
+) -> Option<(i32, i32)> {
+    let (caster_x, caster_y) = objects[caster_id].pos();
+    let mut rng = rand::rng();
+    for _ in 0..MONSTER_BLINK_TRIES {
+        let x = caster_x + rng.random_range(-MONSTER_BLINK_RANGE..=MONSTER_BLINK_RANGE);
+        let y = caster_y + rng.random_range(-MONSTER_BLINK_RANGE..=MONSTER_BLINK_RANGE);
+        let in_bounds = x > 0 && x < MAP_WIDTH - 1 && y > 0 && y < MAP_HEIGHT - 1;
+        let far_enough = objects[threat_id].distance(x, y) >= RANGED_KEEP_DISTANCE;
+        if in_bounds && far_enough && !is_blocked(x, y, &game.map, objects) {
+            return Some((x, y));
+        }
+    }
+    None
+}
@@ -0,0 +1,212 @@
+use crate::game::Game;
+use crate::object::Object;
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum StatusKind {
+    Confused,
+    Frozen,
+    Poisoned,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StackRule {
+    Refresh,
+    Stack,
+    Ignore,
+}
+
+impl StatusKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            StatusKind::Confused => "Confused",
+            StatusKind::Frozen => "Frozen",
+            StatusKind::Poisoned => "Poisoned",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            StatusKind::Confused => LIGHT_GREEN,
+            StatusKind::Frozen => LIGHT_BLUE,
+            StatusKind::Poisoned => LIME,
+        }
+    }
+
+    pub fn stack_rule(self) -> StackRule {
+        match self {
+            StatusKind::Confused => StackRule::Refresh,
+            StatusKind::Frozen => StackRule::Ignore,
+            StatusKind::Poisoned => StackRule::Stack,
+        }
+    }
+
+    pub fn on_apply(self, target: &mut Object, effect: &StatusEffect, game: &mut Game) {
+        let callback: fn(&mut Object, &StatusEffect, &mut Game) = match self {
+            StatusKind::Confused => confused_apply,
+            StatusKind::Frozen => frozen_apply,
+            StatusKind::Poisoned => poisoned_apply,
+        };
+        callback(target, effect, game)
+    }
+
+    pub fn on_tick(self, target: &mut Object, effect: &StatusEffect, game: &mut Game) {
+        let callback: fn(&mut Object, &StatusEffect, &mut Game) = match self {
+            StatusKind::Confused | StatusKind::Frozen => no_tick,
+            StatusKind::Poisoned => poisoned_tick,
+        };
+        callback(target, effect, game)
+    }
+
+    pub fn on_expire(self, target: &mut Object, effect: &StatusEffect, game: &mut Game) {
+        let callback: fn(&mut Object, &StatusEffect, &mut Game) = match self {
+            StatusKind::Confused => confused_expire,
+            StatusKind::Frozen => frozen_expire,
+            StatusKind::Poisoned => poisoned_expire,
+        };
+        callback(target, effect, game)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub turns: i32,
+    pub potency: i32,
+}
+
+impl StatusEffect {
+    pub fn new(kind: StatusKind, turns: i32, potency: i32) -> Self {
+        StatusEffect {
+            kind,
+            turns,
+            potency,
+        }
+    }
+}
+
+pub fn apply_status(target: &mut Object, effect: StatusEffect, game: &mut Game) {
+    if !target.alive {
+        return;
+    }
+    let existing = target
+        .status_effects
+        .iter_mut()
+        .find(|e| e.kind == effect.kind);
+    match (existing, effect.kind.stack_rule()) {
+        (Some(_), StackRule::Ignore) => return,
+        (Some(existing), StackRule::Refresh) => {
+            existing.turns = existing.turns.max(effect.turns);
+            existing.potency = existing.potency.max(effect.potency);
+        }
+        (Some(_), StackRule::Stack) | (None, _) => {
+            target.status_effects.push(effect);
+        }
+    }
+    effect.kind.on_apply(target, &effect, game);
+}
+
+pub fn tick_status_effects(target: &mut Object, game: &mut Game) {
+    let mut index = 0;
+    while index < target.status_effects.len() {
+        let effect = target.status_effects[index];
+        effect.kind.on_tick(target, &effect, game);
+        if !target.alive {
+            target.status_effects.clear();
+            return;
+        }
+
+        target.status_effects[index].turns -= 1;
+        if target.status_effects[index].turns <= 0 {
+            let effect = target.status_effects.remove(index);
+            effect.kind.on_expire(target, &effect, game);
+        } else {
+            index += 1;
+        }
+    }
+}
+
+fn no_tick(_target: &mut Object, _effect: &StatusEffect, _game: &mut Game) {}
+
+fn confused_apply(target: &mut Object, _effect: &StatusEffect, game: &mut Game) {
+    if target.is_player() {
+        game.messages.add(
+            "Your head spins, and the world tilts around you!",
+            LIGHT_GREEN,
+        );
+    } else {
+        game.messages.add(
+            format!(
+                "The eyes of {} look vacant, as he starts to stumble around!",
+                target.name
+            ),
+            LIGHT_GREEN,
+        );
+    }
+}
+
+fn confused_expire(target: &mut Object, _effect: &StatusEffect, game: &mut Game) {
+    if target.is_player() {
+        game.messages
+            .add("You are no longer confused.", LIGHT_GREEN);
+    } else {
+        game.messages
+            .add(format!("The {} is no longer confused!", target.name), RED);
+    }
+}
+
+fn frozen_apply(target: &mut Object, _effect: &StatusEffect, game: &mut Game) {
+    if target.is_player() {
+        game.messages
+            .add("You are frozen solid and cannot move!", LIGHT_BLUE);
+    } else {
+        game.messages.add(
+            format!("The {} is frozen solid and cannot move!", target.name),
+            LIGHT_BLUE,
+        );
+    }
+}
+
+fn frozen_expire(target: &mut Object, _effect: &StatusEffect, game: &mut Game) {
+    if target.is_player() {
+        game.messages
+            .add("You thaw out and can move again.", LIGHT_BLUE);
+    } else {
+        game.messages
+            .add(format!("The {} is no longer frozen!", target.name), RED);
+    }
+}
+
+fn poisoned_apply(target: &mut Object, _effect: &StatusEffect, game: &mut Game) {
+    if target.is_player() {
+        game.messages
+            .add("You feel poison burning in your veins!", LIME);
+    } else {
+        game.messages
+            .add(format!("The {} is poisoned!", target.name), LIME);
+    }
+}
+
+fn poisoned_tick(target: &mut Object, effect: &StatusEffect, game: &mut Game) {
+    game.messages.add(
+        format!(
+            "The {} suffers {} poison damage.",
+            target.name, effect.potency
+        ),
+        LIME,
+    );
+    target.take_damage(effect.potency, game);
+}
+
+fn poisoned_expire(target: &mut Object, _effect: &StatusEffect, game: &mut Game) {
+    if target.has_status(StatusKind::Poisoned) {
+        return;
+    }
+    if target.is_player() {
+        game.messages.add("The poison wears off.", LIME);
+    } else {
+        game.messages
+            .add(format!("The {} is no longer poisoned.", target.name), LIME);
+    }
+}
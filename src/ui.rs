@@ -1,12 +1,17 @@
 use crate::constants::*;
+use crate::entity::Objects;
 use crate::game::{Game, Tcod, initialise_fov, load_game, new_game, play_game};
+use crate::identify::Identification;
+use crate::item::get_equipped_in_slot;
 use crate::meta::{self, PermanentUpgrades, save_meta};
-use crate::object::Object;
+use crate::object::{Equipment, Fighter, Object, Slot};
+use crate::projectile::Projectile;
 use tcod::colors::*;
 use tcod::console::*;
-use tcod::input::Mouse;
+use tcod::input::{KeyCode, Mouse};
 use tcod::map::Map as FovMap;
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_bar(
     panel: &mut Offscreen,
     x: i32,
@@ -34,13 +39,13 @@ pub fn render_bar(
         y,
         BackgroundFlag::None,
         TextAlignment::Center,
-        &format!("{}: {}/{}", name, value, maximum),
+        format!("{}: {}/{}", name, value, maximum),
     );
 }
 
-pub fn render_all(tcod: &mut Tcod, game: &mut Game, objects: &[Object], fov_recompute: bool) {
+pub fn render_all(tcod: &mut Tcod, game: &mut Game, objects: &Objects, fov_recompute: bool) {
     if fov_recompute {
-        let player = &objects[PLAYER];
+        let player = objects.player();
         tcod.fov
             .compute_fov(player.x, player.y, TORCH_RADIUS, FOV_LIGHT_WALLS, FOV_ALGO);
     }
@@ -74,12 +79,17 @@ pub fn render_all(tcod: &mut Tcod, game: &mut Game, objects: &[Object], fov_reco
                 || (o.always_visible && game.map[o.x as usize][o.y as usize].explored)
         })
         .collect();
-    to_draw.sort_by(|o1, o2| o1.blocks.cmp(&o2.blocks));
+    to_draw.sort_by_key(|o| o.blocks);
 
     for object in &to_draw {
         object.draw(&mut tcod.con);
     }
 
+    if let Some(((x, y), glyph, color)) = tcod.projectile {
+        tcod.con.set_default_foreground(color);
+        tcod.con.put_char(x, y, glyph, BackgroundFlag::None);
+    }
+
     blit(
         &tcod.con,
         (0, 0),
@@ -104,8 +114,8 @@ pub fn render_all(tcod: &mut Tcod, game: &mut Game, objects: &[Object], fov_reco
         tcod.panel.print_rect(MSG_X, y, MSG_WIDTH, 0, msg);
     }
 
-    let hp = objects[PLAYER].fighter.map_or(0, |f| f.hp);
-    let max_hp = objects[PLAYER].max_hp(game);
+    let hp = objects.player().get::<Fighter>().map_or(0, |f| f.hp);
+    let max_hp = objects.player().max_hp();
     render_bar(
         &mut tcod.panel,
         1,
@@ -118,21 +128,72 @@ pub fn render_all(tcod: &mut Tcod, game: &mut Game, objects: &[Object], fov_reco
         DARKER_RED,
     );
 
+    let boss = objects
+        .iter()
+        .find(|object| object.alive && object.is_boss() && tcod.fov.is_in_fov(object.x, object.y));
+    if let Some(boss) = boss {
+        render_bar(
+            &mut tcod.panel,
+            1,
+            2,
+            BAR_WIDTH,
+            &boss.name,
+            boss.get::<Fighter>().map_or(0, |f| f.hp),
+            boss.max_hp(),
+            ORANGE,
+            DARKER_ORANGE,
+        );
+    }
+
     tcod.panel.print_ex(
         1,
         3,
         BackgroundFlag::None,
         TextAlignment::Left,
-        format!("Dungeon level: {}", game.dungeon_level),
+        format!("Dungeon level: {}  Gold: {}", game.dungeon_level, game.gold),
     );
 
+    let mut line = 4;
+    for effect in &objects.player().status_effects {
+        if line >= PANEL_HEIGHT {
+            break;
+        }
+        tcod.panel.set_default_foreground(effect.kind.color());
+        tcod.panel.print_ex(
+            1,
+            line,
+            BackgroundFlag::None,
+            TextAlignment::Left,
+            format!("{} ({})", effect.kind.name(), effect.turns),
+        );
+        line += 1;
+    }
+
+    for companion in objects.iter().filter(|o| o.alive && o.is_companion()) {
+        if line >= PANEL_HEIGHT {
+            break;
+        }
+        render_bar(
+            &mut tcod.panel,
+            1,
+            line,
+            BAR_WIDTH,
+            &companion.name,
+            companion.get::<Fighter>().map_or(0, |f| f.hp),
+            companion.max_hp(),
+            LIGHT_GREEN,
+            DARKER_GREEN,
+        );
+        line += 1;
+    }
+
     tcod.panel.set_default_foreground(LIGHT_GREY);
     tcod.panel.print_ex(
         1,
         0,
         BackgroundFlag::None,
         TextAlignment::Left,
-        get_names_under_mouse(tcod.mouse, objects, &tcod.fov),
+        get_names_under_mouse(tcod.mouse, objects, &tcod.fov, &game.identification),
     );
 
     blit(
@@ -146,6 +207,28 @@ pub fn render_all(tcod: &mut Tcod, game: &mut Game, objects: &[Object], fov_reco
     );
 }
 
+pub fn animate_projectile(
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &Objects,
+    origin: (i32, i32),
+    path: &[(i32, i32)],
+    projectile: Projectile,
+) {
+    let (end_x, end_y) = path.last().copied().unwrap_or(origin);
+    let glyph = projectile.glyph(end_x - origin.0, end_y - origin.1);
+    for &(x, y) in path {
+        if !tcod.fov.is_in_fov(x, y) {
+            continue;
+        }
+        tcod.projectile = Some(((x, y), glyph, projectile.color()));
+        tcod.con.clear();
+        render_all(tcod, game, objects, false);
+        tcod.root.flush();
+    }
+    tcod.projectile = None;
+}
+
 pub fn menu<T: AsRef<str>>(
     header: &str,
     options: &[T],
@@ -208,42 +291,122 @@ pub fn menu<T: AsRef<str>>(
     }
 }
 
+pub fn text_input(header: &str, width: i32, root: &mut Root) -> Option<String> {
+    let mut text = String::new();
+    loop {
+        let prompt = format!("{}\n> {}_", header, text);
+        let height = root.get_height_rect(0, 0, width, SCREEN_HEIGHT, &prompt);
+        let mut window = Offscreen::new(width, height);
+        window.set_default_foreground(WHITE);
+        window.print_rect_ex(
+            0,
+            0,
+            width,
+            height,
+            BackgroundFlag::None,
+            TextAlignment::Left,
+            &prompt,
+        );
+        let x = SCREEN_WIDTH / 2 - width / 2;
+        let y = SCREEN_HEIGHT / 2 - height / 2;
+        blit(&window, (0, 0), (width, height), root, (x, y), 1.0, 0.7);
+
+        root.flush();
+        let key = root.wait_for_keypress(true);
+        match key.code {
+            KeyCode::Enter => return Some(text.trim().to_string()),
+            KeyCode::Escape => return None,
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            _ if !key.printable.is_control() && text.len() < NOTE_MAX_LENGTH => {
+                text.push(key.printable)
+            }
+            _ => {}
+        }
+    }
+}
+
 pub fn msgbox(text: &str, width: i32, root: &mut Root) {
     let options: &[&str] = &[];
     menu(text, options, width, root);
 }
 
-pub fn inventory_menu(inventory: &[Object], header: &str, root: &mut Root) -> Option<usize> {
-    let options = if inventory.len() == 0 {
+pub fn equipment_screen(player: &Object, identification: &Identification, root: &mut Root) {
+    let inventory = player.inventory();
+    let mut text = String::from("Equipment\n\n");
+    for slot in Slot::ALL {
+        let worn = get_equipped_in_slot(slot, inventory).map_or("-".to_string(), |index| {
+            identification.name(&inventory[index])
+        });
+        text += &format!("{:>17}: {}\n", slot.to_string(), worn);
+    }
+
+    let equipped = player.get_all_equipped();
+    let total = |bonus: fn(&Equipment) -> i32| equipped.iter().map(bonus).sum::<i32>();
+    text += &format!(
+        "\nAttack: {} ({:+})\nFire damage: {}\nDefense: {} ({:+})\nMaximum HP: {} ({:+})",
+        player.power(),
+        player.power_bonus(),
+        player.fire_damage(),
+        player.defense(),
+        total(|e| e.defense_bonus),
+        player.max_hp(),
+        total(|e| e.max_hp_bonus),
+    );
+    msgbox(&text, EQUIPMENT_SCREEN_WIDTH, root);
+}
+
+pub fn inventory_menu(
+    inventory: &[Object],
+    identification: &Identification,
+    header: &str,
+    root: &mut Root,
+) -> Option<usize> {
+    let options = if inventory.is_empty() {
         vec!["Inventory is empty.".into()]
     } else {
         inventory
             .iter()
-            .map(|item| match item.equipment {
+            .map(|item| match item.get::<Equipment>() {
                 Some(equipment) if equipment.equipped => {
-                    format!("{} (on {})", item.name, equipment.slot)
+                    format!(
+                        "{} (on {})",
+                        identification.display_name(item),
+                        equipment.slot
+                    )
                 }
-                _ => item.name.clone(),
+                _ => identification.display_name(item),
             })
             .collect()
     };
 
     let inventory_index = menu(header, &options, INVENTORY_WIDTH, root);
 
-    if inventory.len() > 0 {
+    if !inventory.is_empty() {
         inventory_index
     } else {
         None
     }
 }
 
-fn get_names_under_mouse(mouse: Mouse, objects: &[Object], fov_map: &FovMap) -> String {
+pub fn quantity_menu(header: &str, max: i32, root: &mut Root) -> Option<i32> {
+    let options: Vec<_> = (1..=max).map(|amount| amount.to_string()).collect();
+    menu(header, &options, INVENTORY_WIDTH, root).map(|index| index as i32 + 1)
+}
+
+fn get_names_under_mouse(
+    mouse: Mouse,
+    objects: &Objects,
+    fov_map: &FovMap,
+    identification: &Identification,
+) -> String {
     let (x, y) = (mouse.cx as i32, mouse.cy as i32);
 
     let names = objects
         .iter()
         .filter(|obj| obj.pos() == (x, y) && fov_map.is_in_fov(obj.x, obj.y))
-        .map(|obj| obj.name.clone())
+        .map(|obj| identification.name(obj))
         .collect::<Vec<_>>();
 
     names.join(", ")
@@ -252,7 +415,7 @@ fn get_names_under_mouse(mouse: Mouse, objects: &[Object], fov_map: &FovMap) ->
 pub fn upgrade_menu(root: &mut Root, upgrades: &mut PermanentUpgrades) {
     let mut choice = None;
 
-    while choice != Some(3) {
+    while choice != Some(4) {
         let hp_cost = 50 + (upgrades.bonus_hp / 2) * 10;
         let power_cost = 100 + (upgrades.bonus_power) * 50;
         let defense_cost = 100 + (upgrades.bonus_defense) * 50;
@@ -265,31 +428,34 @@ pub fn upgrade_menu(root: &mut Root, upgrades: &mut PermanentUpgrades) {
             format!("Fortitude (+2 HP) - Cost: {}", hp_cost),
             format!("Ferocity (+1 Power) - Cost: {}", power_cost),
             format!("Resilience (+1 Defense) - Cost: {}", defense_cost),
+            if upgrades.companion {
+                "Loyal Hound - Unlocked".to_string()
+            } else {
+                format!("Loyal Hound - Cost: {}", COMPANION_UNLOCK_COST)
+            },
             "Return to menu".to_string(),
         ];
 
         choice = menu(&header, options, 50, root);
 
         match choice {
-            Some(0) => {
-                if upgrades.echoes >= hp_cost {
-                    upgrades.echoes -= hp_cost;
-                    upgrades.bonus_hp += 2;
-                }
+            Some(0) if upgrades.echoes >= hp_cost => {
+                upgrades.echoes -= hp_cost;
+                upgrades.bonus_hp += 2;
             }
-            Some(1) => {
-                if upgrades.echoes >= power_cost {
-                    upgrades.echoes -= power_cost;
-                    upgrades.bonus_power += 2;
-                }
+            Some(1) if upgrades.echoes >= power_cost => {
+                upgrades.echoes -= power_cost;
+                upgrades.bonus_power += 2;
             }
-            Some(2) => {
-                if upgrades.echoes >= defense_cost {
-                    upgrades.echoes -= defense_cost;
-                    upgrades.bonus_defense += 2;
-                }
+            Some(2) if upgrades.echoes >= defense_cost => {
+                upgrades.echoes -= defense_cost;
+                upgrades.bonus_defense += 2;
             }
-            Some(3) => {
+            Some(3) if !upgrades.companion && upgrades.echoes >= COMPANION_UNLOCK_COST => {
+                upgrades.echoes -= COMPANION_UNLOCK_COST;
+                upgrades.companion = true;
+            }
+            Some(4) => {
                 break;
             }
             _ => {}
@@ -299,11 +465,9 @@ pub fn upgrade_menu(root: &mut Root, upgrades: &mut PermanentUpgrades) {
 }
 
 pub fn main_menu(tcod: &mut Tcod) {
-    let img = tcod::image::Image::from_file("menu_background.png")
-        .ok()
-        .expect("Background image not found");
+    let img =
+        tcod::image::Image::from_file("menu_background.png").expect("Background image not found");
 
-    
     while !tcod.root.window_closed() {
         let mut upgrades = meta::load_meta().unwrap_or_else(|_| meta::PermanentUpgrades::new());
         tcod::image::blit_2x(&img, (0, 0), (-1, -1), &mut tcod.root, (0, 0));